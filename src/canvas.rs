@@ -1,10 +1,44 @@
-use crate::coordinates::Coordinates;
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Clone)]
+use crate::{color::Style, coordinates::Coordinates};
+
+/// What is shown in one spot on the canvas, and the colours it is shown in
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Cell {
+    pub glyph: String,
+    pub style: Style,
+}
+
+impl Cell {
+    pub fn new(glyph: &str, style: Style) -> Self {
+        Self {
+            glyph: glyph.to_string(),
+            style,
+        }
+    }
+
+    /// How many columns the cell takes up in the terminal
+    pub fn width(&self) -> usize {
+        self.glyph.width()
+    }
+}
+
+impl From<char> for Cell {
+    fn from(char: char) -> Self {
+        Self {
+            glyph: char.to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+/// A grid of cells to draw. A cell can take up more than one column.
+#[derive(Clone, Default)]
 pub struct Canvas {
-    matrix: Vec<Vec<char>>,
+    matrix: Vec<Vec<Cell>>,
     x_res: usize,
     y_res: usize,
+    cell_width: usize, // How many columns each cell of the filled area takes up
 }
 
 impl Canvas {
@@ -13,21 +47,32 @@ impl Canvas {
             matrix: vec![],
             x_res: 0,
             y_res: 0,
+            cell_width: 1,
         }
     }
 
-    pub fn fill(&mut self, char: char, x_res: usize, y_res: usize) {
-        self.matrix = vec![vec![char; y_res]; x_res];
+    /// Fill the canvas with `y_res` rows of `x_res` cells, each `cell_width` columns wide. Glyphs set in the filled area
+    /// are padded out to the cell width, so the columns line up whatever the glyphs are.
+    pub fn fill(&mut self, cell: Cell, x_res: usize, y_res: usize, cell_width: usize) {
+        self.cell_width = cell_width;
+        self.matrix = vec![vec![self.pad(cell); x_res]; y_res];
         self.x_res = x_res;
         self.y_res = y_res;
     }
 
+    fn pad(&self, mut cell: Cell) -> Cell {
+        let padding = self.cell_width.saturating_sub(cell.width());
+
+        cell.glyph.push_str(&" ".repeat(padding));
+        cell
+    }
+
     pub fn to_buffer(&self) -> String {
         let mut buf = String::new();
 
         for row in &self.matrix {
-            for val in row {
-                buf.push(*val);
+            for cell in row {
+                buf.push_str(&cell.glyph);
             }
 
             buf.push('\n');
@@ -36,26 +81,136 @@ impl Canvas {
         buf
     }
 
-    pub fn set_coord(&mut self, coordinate: &Coordinates, new_char: char) -> bool {
+    pub fn get_rows(&self) -> &[Vec<Cell>] {
+        &self.matrix
+    }
+
+    pub fn set_coord(&mut self, coordinate: &Coordinates, cell: Cell) -> bool {
         let (x, y) = coordinate.as_tuple();
+        let cell = self.pad(cell);
 
         self.matrix.get_mut(y).is_some_and(|row| {
             row.get_mut(x).is_some_and(|value| {
-                *value = new_char;
+                *value = cell;
 
                 true
             })
         })
     }
 
+    pub fn get_coord(&self, coordinate: &Coordinates) -> Option<&Cell> {
+        let (x, y) = coordinate.as_tuple();
+
+        self.matrix.get(y).and_then(|row| row.get(x))
+    }
+
+    /// Replace an existing row, returning false if there is no row there
+    pub fn set_row(&mut self, y: usize, row: Vec<Cell>) -> bool {
+        self.matrix.get_mut(y).is_some_and(|existing| {
+            *existing = row;
+
+            true
+        })
+    }
+
+    /// Add a row of text, one character per cell
     pub fn add_row_string<S: AsRef<str>>(&mut self, row: S) {
-        let row_vec = row.as_ref().chars().collect::<Vec<char>>();
+        let row_vec = row.as_ref().chars().map(Cell::from).collect();
 
         self.add_row(row_vec);
     }
 
-    pub fn add_row(&mut self, row: Vec<char>) {
+    pub fn add_row(&mut self, row: Vec<Cell>) {
         self.matrix.push(row);
         self.y_res += 1;
     }
+
+    /// Break rows wider than the terminal onto rows of their own, after the last space that fits where there is one.
+    /// Left to the terminal, a long row would wrap by itself and then be drawn over by the row under it.
+    pub fn wrap(&mut self, columns: usize) {
+        let mut matrix = Vec::with_capacity(self.matrix.len());
+
+        for mut row in std::mem::take(&mut self.matrix) {
+            loop {
+                let mut width = 0;
+                let fits = row
+                    .iter()
+                    .take_while(|cell| {
+                        width += cell.width();
+                        width <= columns
+                    })
+                    .count();
+
+                if fits == row.len() {
+                    matrix.push(row);
+
+                    break;
+                }
+
+                let rest = match row[1..=fits].iter().rposition(|cell| cell.glyph == " ") {
+                    Some(space) => {
+                        let mut rest = row.split_off(space + 1);
+
+                        rest.remove(0);
+                        rest
+                    }
+                    // A cell wider than the terminal still has to go somewhere
+                    None => row.split_off(fits.max(1)),
+                };
+
+                matrix.push(row);
+                row = rest;
+            }
+        }
+
+        self.y_res = matrix.len();
+        self.matrix = matrix;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_makes_rows_as_wide_as_x_res() {
+        let mut canvas = Canvas::new();
+
+        canvas.fill(Cell::from('.'), 6, 3, 1);
+        canvas.set_coord(&Coordinates::new(5, 2), Cell::from('#'));
+
+        assert_eq!(canvas.to_buffer(), "......\n......\n.....#\n");
+        assert_eq!(
+            canvas.get_coord(&Coordinates::new(5, 2)),
+            Some(&Cell::from('#'))
+        );
+        assert_eq!(canvas.get_coord(&Coordinates::new(2, 5)), None);
+    }
+
+    #[test]
+    fn rows_wider_than_the_terminal_are_broken_at_a_space() {
+        let mut canvas = Canvas::new();
+
+        canvas.add_row_string("Press [S] to play slowly");
+        canvas.add_row_string("short");
+        canvas.add_row_string("abcdefghij");
+        canvas.wrap(8);
+
+        assert_eq!(
+            canvas.to_buffer(),
+            "Press\n[S] to\nplay\nslowly\nshort\nabcdefgh\nij\n"
+        );
+    }
+
+    #[test]
+    fn narrow_glyphs_are_padded_to_the_cell_width() {
+        let mut canvas = Canvas::new();
+
+        canvas.fill(Cell::from('.'), 3, 1, 2);
+        canvas.set_coord(&Coordinates::new(0, 0), Cell::from('🍏'));
+        canvas.set_coord(&Coordinates::new(1, 0), Cell::new("[]", Style::default()));
+
+        assert_eq!(canvas.to_buffer(), "🍏[]. \n");
+        assert!(canvas.get_rows()[0].iter().all(|cell| cell.width() == 2));
+    }
 }
@@ -0,0 +1,271 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::{
+    color::ColorMode,
+    config::GameConfig,
+    controller::ControllerKind,
+    game::{GlyphTheme, Palette},
+    keymap::KeyPreset,
+};
+
+pub const USAGE: &str = "Usage: rust-text-snake [options]
+
+Options:
+  --config <file>                 Read the config from this file instead of the default location
+  --seed <number>                 Seed the spawns, so the same seed always plays out the same game
+  --record <file>                 Save a replay of the game to this file when it ends
+  --replay <file>                 Watch a recorded replay
+  --fit                           Make the map as big as the terminal allows
+  --glyphs <emoji|unicode|ascii>  Characters to draw the game with, picked to suit the terminal if left out
+  --color <truecolor|256|16|none> How many colours to use, picked to suit the terminal if left out
+  --theme <file>                  Read the colours from this file
+  --palette <standard|color_blind|high_contrast>
+                                  Colours to start with, the others also tell powerups apart by shape
+  --keys <arrows|wasd|vim>        Keys to steer the snake with
+  --screen-reader                 Announce what happens line by line for a screen reader, instead of drawing the map
+  --player-one <keyboard|autopilot>
+                                  Who steers the first snake
+  --player-two <keyboard|autopilot>
+                                  Who steers the second snake in versus games
+  --autopilot                     Let the bot steer the snake, the same as --player-one autopilot
+  --width <number>                Width of the map
+  --height <number>               Height of the map
+  --max-width <number>            Width the map grow powerup stops at
+  --max-height <number>           Height the map grow powerup stops at
+  --speed <ms>                    Starting tick speed
+  --slowdown-penalty <ms>         Added to the tick speed by the slowdown powerup
+  --apples <number>               Apples on the map at once
+  --supersnake-duration <ticks>   How long the supersnake powerup lasts
+  --slowdown-duration <ticks>     How long the slowdown powerup lasts
+  --map-wrap-duration <ticks>     How long the map wrap powerup lasts
+  --supersnake-every <points>     Spawn the supersnake powerup every so many points, 0 turns it off
+  --slowdown-every <points>       Spawn the slowdown powerup every so many points, 0 turns it off
+  --map-wrap-every <points>       Spawn the map wrap powerup every so many points, 0 turns it off
+  --map-grow-every <points>       Spawn the map grow powerup every so many points, 0 turns it off
+  --max-queued-turns <number>     How many quick turns can wait for the snake to catch up
+  --help                          Show this message";
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("Missing value for {0}.")]
+    MissingValue(String),
+    #[error("Invalid value '{value}' for {flag}.")]
+    InvalidValue { flag: String, value: String },
+    #[error("Unknown argument '{0}'.\n\n{USAGE}")]
+    UnknownArgument(String),
+    #[error("{0} can't be used with --replay.")]
+    NotWithReplay(&'static str),
+}
+
+#[derive(Default)]
+pub struct CliArgs {
+    pub help: bool,
+    pub config: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub fit_terminal: bool,
+    pub glyphs: Option<GlyphTheme>,
+    pub color: Option<ColorMode>,
+    pub theme: Option<PathBuf>,
+    pub palette: Option<Palette>,
+    pub keys: Option<KeyPreset>,
+    pub screen_reader: bool,
+    pub player_one: Option<ControllerKind>,
+    pub player_two: Option<ControllerKind>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub max_width: Option<usize>,
+    pub max_height: Option<usize>,
+    pub tick_speed: Option<u64>,
+    pub slowdown_penalty: Option<u64>,
+    pub apple_count: Option<usize>,
+    pub supersnake_duration: Option<u64>,
+    pub slowdown_duration: Option<u64>,
+    pub map_wrap_duration: Option<u64>,
+    pub supersnake_every: Option<u64>,
+    pub slowdown_every: Option<u64>,
+    pub map_wrap_every: Option<u64>,
+    pub map_grow_every: Option<u64>,
+    pub max_queued_turns: Option<usize>,
+}
+
+impl CliArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut cli_args = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--help" | "-h" => cli_args.help = true,
+                "--config" => cli_args.config = Some(parse_value(&arg, args.next())?),
+                "--seed" => cli_args.seed = Some(parse_value(&arg, args.next())?),
+                "--record" => cli_args.record = Some(parse_value(&arg, args.next())?),
+                "--replay" => cli_args.replay = Some(parse_value(&arg, args.next())?),
+                "--fit" => cli_args.fit_terminal = true,
+                "--glyphs" => cli_args.glyphs = Some(parse_value(&arg, args.next())?),
+                "--color" => cli_args.color = Some(parse_value(&arg, args.next())?),
+                "--theme" => cli_args.theme = Some(parse_value(&arg, args.next())?),
+                "--palette" => cli_args.palette = Some(parse_value(&arg, args.next())?),
+                "--keys" => cli_args.keys = Some(parse_value(&arg, args.next())?),
+                "--screen-reader" => cli_args.screen_reader = true,
+                "--player-one" => cli_args.player_one = Some(parse_value(&arg, args.next())?),
+                "--player-two" => cli_args.player_two = Some(parse_value(&arg, args.next())?),
+                "--autopilot" => cli_args.player_one = Some(ControllerKind::Autopilot),
+                "--width" => cli_args.width = Some(parse_value(&arg, args.next())?),
+                "--height" => cli_args.height = Some(parse_value(&arg, args.next())?),
+                "--max-width" => cli_args.max_width = Some(parse_value(&arg, args.next())?),
+                "--max-height" => cli_args.max_height = Some(parse_value(&arg, args.next())?),
+                "--speed" => cli_args.tick_speed = Some(parse_value(&arg, args.next())?),
+                "--slowdown-penalty" => {
+                    cli_args.slowdown_penalty = Some(parse_value(&arg, args.next())?)
+                }
+                "--apples" => cli_args.apple_count = Some(parse_value(&arg, args.next())?),
+                "--supersnake-duration" => {
+                    cli_args.supersnake_duration = Some(parse_value(&arg, args.next())?)
+                }
+                "--slowdown-duration" => {
+                    cli_args.slowdown_duration = Some(parse_value(&arg, args.next())?)
+                }
+                "--map-wrap-duration" => {
+                    cli_args.map_wrap_duration = Some(parse_value(&arg, args.next())?)
+                }
+                "--supersnake-every" => {
+                    cli_args.supersnake_every = Some(parse_value(&arg, args.next())?)
+                }
+                "--slowdown-every" => {
+                    cli_args.slowdown_every = Some(parse_value(&arg, args.next())?)
+                }
+                "--map-wrap-every" => {
+                    cli_args.map_wrap_every = Some(parse_value(&arg, args.next())?)
+                }
+                "--map-grow-every" => {
+                    cli_args.map_grow_every = Some(parse_value(&arg, args.next())?)
+                }
+                "--max-queued-turns" => {
+                    cli_args.max_queued_turns = Some(parse_value(&arg, args.next())?)
+                }
+                _ => return Err(CliError::UnknownArgument(arg)),
+            }
+        }
+
+        // A replay brings its own seed, and watching one isn't a game that can be recorded
+        if cli_args.replay.is_some() {
+            if cli_args.seed.is_some() {
+                return Err(CliError::NotWithReplay("--seed"));
+            }
+
+            if cli_args.record.is_some() {
+                return Err(CliError::NotWithReplay("--record"));
+            }
+        }
+
+        Ok(cli_args)
+    }
+
+    /// Override the values in the config with any given on the command line
+    pub fn apply_to(&self, config: &mut GameConfig) {
+        override_with(&mut config.width, self.width);
+        override_with(&mut config.height, self.height);
+        override_with(&mut config.max_width, self.max_width);
+        override_with(&mut config.max_height, self.max_height);
+        override_with(&mut config.tick_speed, self.tick_speed);
+        override_with(&mut config.slowdown_penalty, self.slowdown_penalty);
+        override_with(&mut config.apple_count, self.apple_count);
+        override_with(&mut config.supersnake_duration, self.supersnake_duration);
+        override_with(&mut config.slowdown_duration, self.slowdown_duration);
+        override_with(&mut config.map_wrap_duration, self.map_wrap_duration);
+        override_with(&mut config.supersnake_every, self.supersnake_every);
+        override_with(&mut config.slowdown_every, self.slowdown_every);
+        override_with(&mut config.map_wrap_every, self.map_wrap_every);
+        override_with(&mut config.map_grow_every, self.map_grow_every);
+        override_with(&mut config.max_queued_turns, self.max_queued_turns);
+
+        if self.fit_terminal {
+            config.fit_terminal = true;
+        }
+
+        override_with(&mut config.glyphs, self.glyphs.map(Some));
+        override_with(&mut config.color, self.color.map(Some));
+        override_with(&mut config.theme, self.theme.clone().map(Some));
+        override_with(&mut config.palette, self.palette);
+        override_with(&mut config.keymap.preset, self.keys);
+        override_with(&mut config.player_one, self.player_one);
+        override_with(&mut config.player_two, self.player_two);
+
+        if self.screen_reader {
+            config.screen_reader = true;
+        }
+    }
+}
+
+fn override_with<T>(field: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, CliError> {
+    let value = value.ok_or_else(|| CliError::MissingValue(flag.to_string()))?;
+
+    value.parse().map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, CliError> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flags_on_the_command_line_override_the_config() {
+        let cli_args = parse(&[
+            "--width",
+            "30",
+            "--apples",
+            "5",
+            "--seed",
+            "7",
+            "--fit",
+            "--autopilot",
+        ])
+        .unwrap();
+        let mut config = GameConfig::default();
+
+        cli_args.apply_to(&mut config);
+
+        assert_eq!(cli_args.seed, Some(7));
+        assert_eq!(config.width, 30);
+        assert_eq!(config.height, GameConfig::default().height);
+        assert_eq!(config.apple_count, 5);
+        assert!(config.fit_terminal);
+        assert!(config.player_one == ControllerKind::Autopilot);
+    }
+
+    #[test]
+    fn bad_arguments_are_rejected() {
+        assert!(matches!(
+            parse(&["--width"]),
+            Err(CliError::MissingValue(flag)) if flag == "--width"
+        ));
+        assert!(matches!(
+            parse(&["--apples", "lots"]),
+            Err(CliError::InvalidValue { value, .. }) if value == "lots"
+        ));
+        assert!(matches!(
+            parse(&["--jump"]),
+            Err(CliError::UnknownArgument(arg)) if arg == "--jump"
+        ));
+        assert!(matches!(
+            parse(&["--replay", "game.toml", "--seed", "1"]),
+            Err(CliError::NotWithReplay("--seed"))
+        ));
+    }
+}
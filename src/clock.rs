@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use tokio::time::{self, Instant, Interval, MissedTickBehavior};
+
+/// How long ticks are really taking
+#[derive(Clone, Copy)]
+pub struct TickTiming {
+    pub measured: Duration, // How long the last tick actually took
+    pub jitter: Duration,   // How far ticks typically stray from the period
+}
+
+/// Keeps game ticks on a fixed schedule. Time spent updating and drawing comes out of the wait for the next tick
+/// instead of being added on top of it, so the game doesn't slow down as it does more work.
+pub struct GameClock {
+    interval: Interval,
+    period: Duration,
+    scheduled: Instant,         // When the last tick was due
+    last_tick: Option<Instant>, // When the last tick actually happened
+    measured: Duration,
+    jitter: Duration,
+}
+
+impl GameClock {
+    pub fn new(period: Duration) -> Self {
+        let now = Instant::now();
+
+        Self {
+            interval: Self::interval_at(now, period),
+            period,
+            scheduled: now,
+            last_tick: None,
+            measured: period,
+            jitter: Duration::ZERO,
+        }
+    }
+
+    fn interval_at(start: Instant, period: Duration) -> Interval {
+        let mut interval = time::interval_at(start, period);
+
+        // If a tick is missed, carry on from the next one on schedule rather than firing a burst to catch up
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        interval
+    }
+
+    /// Wait for the next tick. Safe to use in a select, a tick that is cancelled is simply waited for again.
+    pub async fn tick(&mut self) {
+        self.scheduled = self.interval.tick().await;
+
+        let now = Instant::now();
+
+        if let Some(last_tick) = self.last_tick {
+            self.measured = now - last_tick;
+
+            // Smoothed the same way RTP measures packet jitter, so one slow frame doesn't swamp the reading
+            let deviation = self.measured.abs_diff(self.period);
+
+            self.jitter = self.jitter + deviation / 16 - self.jitter / 16;
+        }
+
+        self.last_tick = Some(now);
+    }
+
+    /// Change how often the clock ticks. The next tick is due one new period after the last one was due.
+    pub fn set_period(&mut self, period: Duration) {
+        if period == self.period {
+            return;
+        }
+
+        self.period = period;
+        self.interval = Self::interval_at(self.scheduled + period, period);
+    }
+
+    pub fn get_timing(&self) -> TickTiming {
+        TickTiming {
+            measured: self.measured,
+            jitter: self.jitter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn ticks_keep_to_the_schedule() {
+        let start = Instant::now();
+        let mut clock = GameClock::new(Duration::from_millis(100));
+
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Time spent working comes out of the wait instead of pushing the next tick back
+        time::advance(Duration::from_millis(30)).await;
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+        assert_eq!(clock.get_timing().measured, Duration::from_millis(100));
+        assert_eq!(clock.get_timing().jitter, Duration::ZERO);
+
+        // A tick that runs 50ms late only adds a sixteenth of that to the jitter, and the one after it is back on time
+        time::advance(Duration::from_millis(150)).await;
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(250));
+        assert_eq!(clock.get_timing().measured, Duration::from_millis(150));
+        assert_eq!(clock.get_timing().jitter, Duration::from_nanos(3_125_000));
+
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(300));
+        assert_eq!(clock.get_timing().measured, Duration::from_millis(50));
+        assert_eq!(clock.get_timing().jitter, Duration::from_nanos(6_054_688));
+
+        // A new period counts from when the last tick was due, and an on time tick lets the jitter settle
+        clock.set_period(Duration::from_millis(200));
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+        assert_eq!(clock.get_timing().measured, Duration::from_millis(200));
+        assert_eq!(clock.get_timing().jitter, Duration::from_nanos(5_676_270));
+
+        clock.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(700));
+    }
+}
@@ -0,0 +1,191 @@
+use std::{env, str::FromStr};
+
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(try_from = "String")]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    /// Parse a colour written like `#3cc83c`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a colour, write colours like #3cc83c.", value);
+        let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let channel = |start| u8::from_str_radix(&hex[start..start + 2], 16).map_err(|_| invalid());
+
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl TryFrom<String> for Rgb {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// The colours a cell is drawn in. Anything left as None is drawn in the terminal's own colours.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Style {
+    #[serde(rename = "fg")]
+    pub foreground: Option<Rgb>,
+    #[serde(rename = "bg")]
+    pub background: Option<Rgb>,
+}
+
+/// How many colours the terminal can show. Colours are picked in full and brought down to whatever the terminal can do.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ColorMode {
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+    #[serde(rename = "none")]
+    None,
+}
+
+/// The 16 standard terminal colours, with the values xterm gives them
+const ANSI_16: [(Color, Rgb); 16] = [
+    (Color::Black, Rgb(0, 0, 0)),
+    (Color::DarkRed, Rgb(205, 0, 0)),
+    (Color::DarkGreen, Rgb(0, 205, 0)),
+    (Color::DarkYellow, Rgb(205, 205, 0)),
+    (Color::DarkBlue, Rgb(0, 0, 238)),
+    (Color::DarkMagenta, Rgb(205, 0, 205)),
+    (Color::DarkCyan, Rgb(0, 205, 205)),
+    (Color::Grey, Rgb(229, 229, 229)),
+    (Color::DarkGrey, Rgb(127, 127, 127)),
+    (Color::Red, Rgb(255, 0, 0)),
+    (Color::Green, Rgb(0, 255, 0)),
+    (Color::Yellow, Rgb(255, 255, 0)),
+    (Color::Blue, Rgb(92, 92, 255)),
+    (Color::Magenta, Rgb(255, 0, 255)),
+    (Color::Cyan, Rgb(0, 255, 255)),
+    (Color::White, Rgb(255, 255, 255)),
+];
+
+impl ColorMode {
+    /// Work out what the terminal can do. NO_COLOR turns colour off, COLORTERM says whether truecolor works, and
+    /// TERM whether 256 colours do.
+    pub fn detect() -> Self {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Self::None;
+        }
+
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term == "dumb" {
+            Self::None
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// The closest colour to `rgb` the terminal can show, or None if it isn't showing colours
+    pub fn convert(&self, rgb: Rgb) -> Option<Color> {
+        let Rgb(r, g, b) = rgb;
+
+        match self {
+            ColorMode::TrueColor => Some(Color::Rgb { r, g, b }),
+            ColorMode::Ansi256 => Some(Color::AnsiValue(to_ansi_256(rgb))),
+            ColorMode::Ansi16 => ANSI_16
+                .iter()
+                .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+                .map(|(color, _)| *color),
+            ColorMode::None => None,
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "truecolor" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            "none" => Ok(Self::None),
+            _ => Err(()),
+        }
+    }
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// Pick whichever is closer out of the 6x6x6 colour cube and the grey ramp of the 256 colour palette
+fn to_ansi_256(rgb: Rgb) -> u8 {
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&level| CUBE_LEVELS[level].abs_diff(value))
+            .unwrap()
+    };
+    let (r, g, b) = (
+        nearest_level(rgb.0),
+        nearest_level(rgb.1),
+        nearest_level(rgb.2),
+    );
+    let cube = Rgb(CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey_step = (average.saturating_sub(8) / 10).min(23) as u8;
+    let grey_level = 8 + grey_step * 10;
+    let grey = Rgb(grey_level, grey_level, grey_level);
+
+    if distance(rgb, grey) < distance(rgb, cube) {
+        232 + grey_step
+    } else {
+        16 + 36 * r as u8 + 6 * g as u8 + b as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_are_brought_down_to_what_the_terminal_can_show() {
+        let blue = Rgb(60, 120, 240);
+
+        assert_eq!(
+            ColorMode::TrueColor.convert(blue),
+            Some(Color::Rgb {
+                r: 60,
+                g: 120,
+                b: 240
+            })
+        );
+        assert_eq!(ColorMode::Ansi256.convert(blue), Some(Color::AnsiValue(69)));
+        assert_eq!(
+            ColorMode::Ansi256.convert(Rgb(128, 128, 128)),
+            Some(Color::AnsiValue(244))
+        );
+        assert_eq!(ColorMode::Ansi16.convert(blue), Some(Color::Blue));
+        assert_eq!(ColorMode::None.convert(blue), None);
+        assert_eq!("#3c78f0".parse(), Ok(blue));
+        assert!("3c78f0".parse::<Rgb>().is_err());
+    }
+}
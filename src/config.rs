@@ -1 +1,283 @@
-pub const PLAYFIELD_SIZE: usize = 20;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    color::ColorMode,
+    controller::ControllerKind,
+    game::{GlyphTheme, Palette, OBSTACLE_COUNT},
+    keymap::{Keymap, KeymapError},
+    layout::fit_playfield,
+};
+
+/// Smallest map the obstacles can be laid out on
+pub const MIN_PLAYFIELD_SIZE: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Could not access the config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read the config file: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error(
+        "The map must be at least {MIN_PLAYFIELD_SIZE}x{MIN_PLAYFIELD_SIZE}, but it is {0}x{1}."
+    )]
+    PlayfieldTooSmall(usize, usize),
+    #[error("The maximum map size {0}x{1} is smaller than the map size {2}x{3}.")]
+    MaxPlayfieldTooSmall(usize, usize, usize, usize),
+    #[error("The tick speed must be at least 1 ms.")]
+    TickSpeedTooLow,
+    #[error("There must be at least 1 apple, and apples can cover at most half of the free cells on the map.")]
+    InvalidAppleCount,
+    #[error("The {0} powerup must last at least 1 tick.")]
+    PowerupDurationTooShort(&'static str),
+    #[error("At least 1 turn must be able to wait for the next tick.")]
+    NoQueuedTurns,
+    #[error("The keymap can't be used: {0}")]
+    InvalidKeymap(#[from] KeymapError),
+}
+
+/// Everything that tunes how the game plays. All fields are optional in the config file and fall back to the defaults.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub max_width: usize, // The map grow powerup stops appearing once the map reaches this size
+    pub max_height: usize,
+    pub tick_speed: u64, // In ms, the snake speeds up by 1 ms for every point scored
+    pub slowdown_penalty: u64, // In ms, added to the tick speed while the slowdown powerup is active
+    pub apple_count: usize,
+    pub supersnake_duration: u64, // In ticks
+    pub slowdown_duration: u64,
+    pub map_wrap_duration: u64,
+    pub supersnake_every: u64, // Spawns whenever the score is a multiple of this, 0 turns the powerup off
+    pub slowdown_every: u64,
+    pub map_wrap_every: u64,
+    pub map_grow_every: u64,
+    pub max_queued_turns: usize, // Turns pressed faster than the snake moves wait for the coming ticks, up to this many
+    pub fit_terminal: bool, // Ignore the width and height and make the map as big as the terminal allows
+    #[serde(skip_serializing)] // Only changes how the game looks, so it is left out of replays
+    pub glyphs: Option<GlyphTheme>, // Left out to pick whichever suits the terminal
+    #[serde(skip_serializing)]
+    pub color: Option<ColorMode>, // Left out to use as many colours as the terminal can show
+    #[serde(skip_serializing)]
+    pub theme: Option<PathBuf>, // A file of colours to use instead of the default ones
+    #[serde(skip_serializing)]
+    pub palette: Palette, // The colour-blind safe and high contrast palettes also give powerups their own shapes
+    #[serde(skip_serializing)]
+    pub screen_reader: bool, // Announce what happens line by line instead of drawing the map
+    #[serde(skip_serializing)]
+    pub keymap: Keymap,
+    #[serde(skip_serializing)]
+    pub player_one: ControllerKind,
+    #[serde(skip_serializing)]
+    pub player_two: ControllerKind, // Only steers in versus games
+    #[serde(skip_serializing)]
+    pub autopilot: bool, // Deprecated, the same as player_one = "autopilot" and kept so older config files still load
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            height: 20,
+            max_width: 30,
+            max_height: 30,
+            tick_speed: 200,
+            slowdown_penalty: 50,
+            apple_count: 3,
+            supersnake_duration: 100,
+            slowdown_duration: 150,
+            map_wrap_duration: 100,
+            supersnake_every: 25,
+            slowdown_every: 15,
+            map_wrap_every: 20,
+            map_grow_every: 35,
+            max_queued_turns: 2,
+            fit_terminal: false,
+            glyphs: None,
+            color: None,
+            theme: None,
+            palette: Palette::Standard,
+            screen_reader: false,
+            keymap: Keymap::default(),
+            player_one: ControllerKind::Keyboard,
+            player_two: ControllerKind::Keyboard,
+            autopilot: false,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Where the config file is looked for when one isn't given, under the XDG config directory (or the platform equivalent)
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rust-text-snake").join("config.toml"))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let mut config: Self = toml::from_str(&fs::read_to_string(path)?)?;
+
+        if config.autopilot {
+            config.player_one = ControllerKind::Autopilot;
+        }
+
+        Ok(config)
+    }
+
+    /// Load the config file at the default path, or the defaults if there isn't one
+    pub fn load_default() -> Result<Self, ConfigError> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Size the map to fill a terminal of the given size, making sure the map grow powerup can't push it past the edges
+    pub fn fit_to_terminal(&mut self, columns: u16, rows: u16) {
+        let (width, height) = fit_playfield(columns, rows);
+
+        self.width = width;
+        self.height = height;
+        self.max_width = width;
+        self.max_height = height;
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.width < MIN_PLAYFIELD_SIZE || self.height < MIN_PLAYFIELD_SIZE {
+            return Err(ConfigError::PlayfieldTooSmall(self.width, self.height));
+        }
+
+        if self.max_width < self.width || self.max_height < self.height {
+            return Err(ConfigError::MaxPlayfieldTooSmall(
+                self.max_width,
+                self.max_height,
+                self.width,
+                self.height,
+            ));
+        }
+
+        if self.tick_speed == 0 {
+            return Err(ConfigError::TickSpeedTooLow);
+        }
+
+        // Half of what's left once the obstacles and the snakes of a versus game are down
+        let free_cells = (self.width * self.height).saturating_sub(OBSTACLE_COUNT + 2);
+
+        if self.apple_count == 0 || self.apple_count > free_cells / 2 {
+            return Err(ConfigError::InvalidAppleCount);
+        }
+
+        for (name, duration) in [
+            ("supersnake", self.supersnake_duration),
+            ("slowdown", self.slowdown_duration),
+            ("map wrap", self.map_wrap_duration),
+        ] {
+            if duration == 0 {
+                return Err(ConfigError::PowerupDurationTooShort(name));
+            }
+        }
+
+        if self.max_queued_turns == 0 {
+            return Err(ConfigError::NoQueuedTurns);
+        }
+
+        self.keymap.validate()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_config_file_only_needs_the_values_it_changes() {
+        let path = std::env::temp_dir().join(format!("snake-config-{}.toml", std::process::id()));
+
+        fs::write(&path, "width = 25\napple_count = 4\nautopilot = true\n").unwrap();
+
+        let config = GameConfig::load(&path);
+
+        fs::write(&path, "widht = 25\n").unwrap();
+
+        let misspelt = GameConfig::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+
+        assert_eq!(config.width, 25);
+        assert_eq!(config.apple_count, 4);
+        assert_eq!(config.height, GameConfig::default().height);
+        assert!(config.player_one == ControllerKind::Autopilot);
+        assert!(matches!(misspelt, Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn configs_the_game_cant_be_played_with_are_rejected() {
+        let validate = |config: GameConfig| config.validate();
+
+        assert!(validate(GameConfig::default()).is_ok());
+        assert!(matches!(
+            validate(GameConfig {
+                width: 9,
+                ..GameConfig::default()
+            }),
+            Err(ConfigError::PlayfieldTooSmall(9, 20))
+        ));
+        assert!(matches!(
+            validate(GameConfig {
+                max_height: 15,
+                ..GameConfig::default()
+            }),
+            Err(ConfigError::MaxPlayfieldTooSmall(30, 15, 20, 20))
+        ));
+        assert!(matches!(
+            validate(GameConfig {
+                tick_speed: 0,
+                ..GameConfig::default()
+            }),
+            Err(ConfigError::TickSpeedTooLow)
+        ));
+        assert!(matches!(
+            validate(GameConfig {
+                map_wrap_duration: 0,
+                ..GameConfig::default()
+            }),
+            Err(ConfigError::PowerupDurationTooShort("map wrap"))
+        ));
+        assert!(matches!(
+            validate(GameConfig {
+                max_queued_turns: 0,
+                ..GameConfig::default()
+            }),
+            Err(ConfigError::NoQueuedTurns)
+        ));
+    }
+
+    #[test]
+    fn apples_only_fit_in_the_cells_the_obstacles_and_snakes_leave() {
+        let small = |apple_count| {
+            GameConfig {
+                width: 10,
+                height: 10,
+                max_width: 10,
+                max_height: 10,
+                apple_count,
+                ..GameConfig::default()
+            }
+            .validate()
+        };
+
+        assert!(small(39).is_ok());
+        assert!(matches!(small(40), Err(ConfigError::InvalidAppleCount)));
+        assert!(matches!(small(50), Err(ConfigError::InvalidAppleCount)));
+        assert!(matches!(small(0), Err(ConfigError::InvalidAppleCount)));
+    }
+}
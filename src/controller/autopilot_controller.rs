@@ -0,0 +1,12 @@
+use crate::game::{autopilot_turn, GameSnapshot, SnakeDirection, SnakeId};
+
+use super::traits::Controller;
+
+/// Steered by the built-in bot
+pub struct AutopilotController;
+
+impl Controller for AutopilotController {
+    fn next_turns(&mut self, game: &GameSnapshot, id: SnakeId) -> Vec<SnakeDirection> {
+        autopilot_turn(game, id).into_iter().collect()
+    }
+}
@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// The controllers that can be picked in the config or on the command line. Scripted and remote controllers need
+/// more than a name to set up, so they're assigned in code.
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ControllerKind {
+    #[default]
+    Keyboard,
+    Autopilot,
+}
+
+impl FromStr for ControllerKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keyboard" => Ok(Self::Keyboard),
+            "autopilot" => Ok(Self::Autopilot),
+            _ => Err(format!("Unknown controller '{}'.", value)),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use tokio::sync::mpsc;
+
+use crate::{
+    config::GameConfig,
+    game::{Game, GameState, SnakeDirection, SnakeId, PLAYER_ONE, PLAYER_TWO},
+};
+
+use super::{
+    autopilot_controller::AutopilotController, controller_kind::ControllerKind,
+    keyboard_controller::KeyboardController, traits::Controller,
+};
+
+/// Which controller steers each snake, set up at startup. A snake without one only moves the way it's going.
+/// Turning a snake always goes through its controller, key presses included.
+#[derive(Default)]
+pub struct Controllers {
+    controllers: HashMap<SnakeId, Box<dyn Controller>>,
+    keys: HashMap<SnakeId, mpsc::UnboundedSender<SnakeDirection>>, // For the snakes steered from the keyboard
+}
+
+impl Controllers {
+    /// The controllers the config picked for each player
+    pub fn from_config(config: &GameConfig) -> Self {
+        let mut controllers = Self::default();
+
+        controllers.assign_kind(PLAYER_ONE, config.player_one);
+        controllers.assign_kind(PLAYER_TWO, config.player_two);
+
+        controllers
+    }
+
+    pub fn assign(&mut self, id: SnakeId, controller: Box<dyn Controller>) {
+        self.controllers.insert(id, controller);
+        self.keys.remove(&id);
+    }
+
+    pub fn assign_kind(&mut self, id: SnakeId, kind: ControllerKind) {
+        match kind {
+            ControllerKind::Keyboard => {
+                let (controller, keys) = KeyboardController::new();
+
+                self.assign(id, Box::new(controller));
+                self.keys.insert(id, keys);
+            }
+            ControllerKind::Autopilot => self.assign(id, Box::new(AutopilotController)),
+        }
+    }
+
+    pub fn takes_keys(&self, id: SnakeId) -> bool {
+        self.keys.contains_key(&id)
+    }
+
+    /// Hand a turning key to the snake's keyboard controller, to be turned on the next tick.
+    /// Keys for a snake the keyboard doesn't steer are ignored, so they can't fight a bot for the wheel, and so are
+    /// keys for a snake that isn't in the game being played, so they can't pile up for a later one.
+    pub fn press(&self, game: &Game, id: SnakeId, direction: SnakeDirection) {
+        if !matches!(game.get_state(), GameState::Playing) || game.get_snake(id).is_none() {
+            return;
+        }
+
+        if let Some(keys) = self.keys.get(&id) {
+            let _ = keys.send(direction);
+        }
+    }
+
+    /// Start every controller afresh for a new game
+    pub fn reset(&mut self) {
+        for controller in self.controllers.values_mut() {
+            controller.reset();
+        }
+    }
+
+    /// Ask every snake's controller for its turns, just before the game ticks. They all decide from the same
+    /// snapshot, so no snake gets to see where another has just turned.
+    ///
+    /// Keys wait here for the tick too, so when pressing the way a slowed snake is going skips ahead, the skipped
+    /// ticks run back to back now rather than as each key came in. Skipping only happens with a single snake, so
+    /// there's no other controller left deciding from a snapshot the skips have made stale.
+    pub fn steer(&mut self, game: &mut Game) {
+        if !matches!(game.get_state(), GameState::Playing) {
+            return;
+        }
+
+        let snapshot = game.snapshot();
+
+        for snake in &snapshot.snakes {
+            let id = snake.get_id();
+            let Some(controller) = self.controllers.get_mut(&id) else {
+                continue;
+            };
+
+            for direction in controller.next_turns(&snapshot, id) {
+                game.turn(id, direction);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        controller::{AutopilotController, ScriptedController},
+        coordinates::Coordinates,
+        game::{Simulation, Snake},
+    };
+
+    #[test]
+    fn keys_turn_the_snake_on_the_ticks_after_they_are_pressed() {
+        let mut simulation = Simulation::new(GameConfig::default(), 1);
+        let mut controllers = Controllers::default();
+        let head = |simulation: &Simulation| {
+            simulation
+                .game()
+                .get_snake(PLAYER_ONE)
+                .and_then(|snake| snake.get_snake().last().copied())
+        };
+
+        controllers.assign_kind(PLAYER_ONE, ControllerKind::Keyboard);
+
+        // Two quick turns take a tick each, and keys for a snake the keyboard doesn't steer go nowhere
+        controllers.press(simulation.game(), PLAYER_ONE, SnakeDirection::Down);
+        controllers.press(simulation.game(), PLAYER_ONE, SnakeDirection::Right);
+        controllers.press(simulation.game(), PLAYER_TWO, SnakeDirection::Up);
+
+        simulation.step_with(&mut controllers);
+        assert_eq!(head(&simulation), Some(Coordinates::new(0, 1)));
+
+        simulation.step_with(&mut controllers);
+        assert_eq!(head(&simulation), Some(Coordinates::new(1, 1)));
+        assert!(!controllers.takes_keys(PLAYER_TWO));
+    }
+
+    #[test]
+    fn a_script_of_the_bots_turns_plays_the_same_game() {
+        let mut simulation = Simulation::new(GameConfig::default(), 7);
+        let mut controllers = Controllers::default();
+
+        controllers.assign(PLAYER_ONE, Box::new(AutopilotController));
+
+        for _ in 0..200 {
+            simulation.step_with(&mut controllers);
+        }
+
+        let played = simulation.into_game();
+        let mut simulation = Simulation::new(GameConfig::default(), 7);
+        let mut controllers = Controllers::default();
+
+        controllers.assign(
+            PLAYER_ONE,
+            Box::new(ScriptedController::from_replay(
+                played.get_replay(),
+                PLAYER_ONE,
+            )),
+        );
+
+        for _ in 0..200 {
+            simulation.step_with(&mut controllers);
+        }
+
+        let scripted = simulation.game();
+
+        assert!(played.get_score() > 0);
+        assert_eq!(scripted.get_score(), played.get_score());
+        assert_eq!(
+            scripted
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone()),
+            played
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone())
+        );
+    }
+}
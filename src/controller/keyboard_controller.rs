@@ -0,0 +1,37 @@
+use tokio::sync::mpsc;
+
+use crate::game::{GameSnapshot, SnakeDirection, SnakeId};
+
+use super::traits::Controller;
+
+/// Steered by a player at the keyboard. The game loop hands over each turning key as it's pressed through the sender
+/// handed out with the controller, and every key pressed since the last tick is turned on the next one.
+pub struct KeyboardController {
+    keys: mpsc::UnboundedReceiver<SnakeDirection>,
+}
+
+impl KeyboardController {
+    /// Keys come in at the speed someone can press them, so there's no need to limit how many can wait
+    pub fn new() -> (Self, mpsc::UnboundedSender<SnakeDirection>) {
+        let (sender, keys) = mpsc::unbounded_channel();
+
+        (Self { keys }, sender)
+    }
+}
+
+impl Controller for KeyboardController {
+    fn next_turns(&mut self, _game: &GameSnapshot, _id: SnakeId) -> Vec<SnakeDirection> {
+        let mut turns = vec![];
+
+        while let Ok(direction) = self.keys.try_recv() {
+            turns.push(direction);
+        }
+
+        turns
+    }
+
+    /// Keys pressed too late for the last game shouldn't turn the snake in the next one
+    fn reset(&mut self) {
+        while self.keys.try_recv().is_ok() {}
+    }
+}
@@ -0,0 +1,15 @@
+mod autopilot_controller;
+mod controller_kind;
+mod controllers;
+mod keyboard_controller;
+mod remote_controller;
+mod scripted_controller;
+mod traits;
+
+pub use autopilot_controller::*;
+pub use controller_kind::*;
+pub use controllers::*;
+pub use keyboard_controller::*;
+pub use remote_controller::*;
+pub use scripted_controller::*;
+pub use traits::*;
@@ -0,0 +1,26 @@
+use tokio::sync::mpsc;
+
+use crate::game::{GameSnapshot, SnakeDirection, SnakeId};
+
+use super::traits::Controller;
+
+/// Steered from somewhere else, like a peer over the network. Whatever talks to the peer sends its turns in through
+/// the sender handed out with the controller, and each tick takes the oldest turn waiting.
+pub struct RemoteController {
+    directions: mpsc::Receiver<SnakeDirection>,
+}
+
+impl RemoteController {
+    /// Up to `buffer` turns can wait for their tick before the sender has to hold off
+    pub fn new(buffer: usize) -> (Self, mpsc::Sender<SnakeDirection>) {
+        let (sender, directions) = mpsc::channel(buffer);
+
+        (Self { directions }, sender)
+    }
+}
+
+impl Controller for RemoteController {
+    fn next_turns(&mut self, _game: &GameSnapshot, _id: SnakeId) -> Vec<SnakeDirection> {
+        self.directions.try_recv().into_iter().collect()
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use crate::game::{GameSnapshot, Replay, SnakeDirection, SnakeId};
+
+use super::traits::Controller;
+
+/// Turns on the ticks they're given, for tests, tutorials or playing back one snake from a replay alongside others
+pub struct ScriptedController {
+    turns: HashMap<u64, SnakeDirection>, // By the tick they apply on
+}
+
+impl ScriptedController {
+    pub fn new(turns: impl IntoIterator<Item = (u64, SnakeDirection)>) -> Self {
+        Self {
+            turns: turns.into_iter().collect(),
+        }
+    }
+
+    /// Follow the turns one snake made in a recorded game
+    pub fn from_replay(replay: &Replay, id: SnakeId) -> Self {
+        Self::new(
+            replay
+                .inputs
+                .iter()
+                .filter(|input| input.snake == id)
+                .map(|input| (input.tick, input.direction)),
+        )
+    }
+}
+
+impl Controller for ScriptedController {
+    fn next_turns(&mut self, game: &GameSnapshot, _id: SnakeId) -> Vec<SnakeDirection> {
+        self.turns
+            .get(&(game.tick + 1))
+            .copied()
+            .into_iter()
+            .collect()
+    }
+}
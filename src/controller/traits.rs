@@ -0,0 +1,13 @@
+use crate::game::{GameSnapshot, SnakeDirection, SnakeId};
+
+/// Something that steers a snake, like the keyboard, a script, a bot or a player somewhere else. The game loop asks
+/// each snake's controller for its turns just before every tick, so new ones can be added without touching the input task.
+/// Controllers only get to look at a snapshot, so none of them can change the game other than by turning.
+pub trait Controller: Send {
+    /// The turns the snake should make before the coming tick, in order. Turns after the first wait in the snake's
+    /// queue for the ticks after it. Empty to carry on.
+    fn next_turns(&mut self, game: &GameSnapshot, id: SnakeId) -> Vec<SnakeDirection>;
+
+    /// Forget anything left waiting from the last game, as a new one is set up. Most controllers have nothing to forget.
+    fn reset(&mut self) {}
+}
@@ -1,6 +1,8 @@
 use rand::Rng;
 
-#[derive(Clone, Copy, PartialEq, Hash, Eq)]
+use crate::game::SnakeDirection;
+
+#[derive(Clone, Copy, PartialEq, Hash, Eq, Debug)]
 pub struct Coordinates {
     pub x: usize,
     pub y: usize,
@@ -11,10 +13,10 @@ impl Coordinates {
         Self { x, y }
     }
 
-    pub fn new_random(max_x: usize, max_y: usize) -> Self {
+    pub fn new_random<R: Rng>(max_x: usize, max_y: usize, rng: &mut R) -> Self {
         Self {
-            x: rand::thread_rng().gen_range(0..max_x),
-            y: rand::thread_rng().gen_range(0..max_y),
+            x: rng.gen_range(0..max_x),
+            y: rng.gen_range(0..max_y),
         }
     }
 
@@ -37,4 +39,27 @@ impl Coordinates {
 
         false
     }
+
+    /// The neighbouring cell in the direction, or None if that's off the map and the map doesn't wrap
+    pub fn step(
+        &self,
+        direction: SnakeDirection,
+        playfield: (usize, usize),
+        can_wrap: bool,
+    ) -> Option<Coordinates> {
+        let (playfield_x, playfield_y) = playfield;
+        let Coordinates { x, y } = *self;
+
+        match direction {
+            SnakeDirection::Right if x < playfield_x - 1 => Some(Coordinates::new(x + 1, y)),
+            SnakeDirection::Right if can_wrap => Some(Coordinates::new(0, y)),
+            SnakeDirection::Left if x > 0 => Some(Coordinates::new(x - 1, y)),
+            SnakeDirection::Left if can_wrap => Some(Coordinates::new(playfield_x - 1, y)),
+            SnakeDirection::Up if y > 0 => Some(Coordinates::new(x, y - 1)),
+            SnakeDirection::Up if can_wrap => Some(Coordinates::new(x, playfield_y - 1)),
+            SnakeDirection::Down if y < playfield_y - 1 => Some(Coordinates::new(x, y + 1)),
+            SnakeDirection::Down if can_wrap => Some(Coordinates::new(x, 0)),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,19 @@
+use crate::{
+    clock::TickTiming,
+    game::{GameEvent, GameSnapshot},
+    leaderboard::LeaderboardEntry,
+};
+
+/// Everything a frontend needs to show one frame: the game itself, plus what the game loop knows around it
+#[derive(Clone)]
+pub struct Frame {
+    pub game: GameSnapshot,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub leaderboard_error: Option<String>,
+    pub replay_status: Option<String>, // Whether the replay of the game that just ended was saved
+    pub tick_timing: Option<TickTiming>, // Only there while the debug HUD is turned on
+    pub events: Vec<GameEvent>,        // What happened in the game since the last frame
+    pub demo: bool, // The game is the attract demo played by the bot while the intro sits idle
+    pub redraw: bool, // The terminal was resized, so what's on screen can't be trusted and has to be drawn again
+    pub next_palette: bool, // The player asked for the next palette on the intro
+}
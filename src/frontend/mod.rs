@@ -0,0 +1,11 @@
+mod frame;
+mod screen_reader_frontend;
+mod text_frontend;
+mod theme;
+mod traits;
+
+pub use frame::*;
+pub use screen_reader_frontend::*;
+pub use text_frontend::*;
+pub use theme::*;
+pub use traits::*;
@@ -0,0 +1,462 @@
+use std::io::{self, Stdout, Write};
+
+use crate::{
+    game::{
+        player_label, EntityType, GameEvent, GameMode, GameSnapshot, GameState, PowerupType, Snake,
+        SnakeBody, SnakeDirection, VersusOutcome, PLAYER_ONE,
+    },
+    keymap::Action,
+};
+
+use super::{frame::Frame, traits::Frontend};
+
+/// Dangers this close get announced every time they get closer
+const WARNING_DISTANCE: usize = 3;
+
+/// Announces the game line by line for a screen reader, instead of redrawing the map. Screens are read out when they
+/// change, and while playing it says what happened and what lies ahead of the snake.
+pub struct ScreenReaderFrontend {
+    stdout: Stdout,
+    screen: Vec<String>, // The lines last read out for the current screen
+    ahead: Option<Ahead>,
+    direction: Option<SnakeDirection>,
+    apple_wanted: bool, // Set after a turn or an apple until the way to the nearest apple has been read out
+}
+
+/// The first thing the snake would run into if it carried on straight
+#[derive(Clone, Copy, PartialEq)]
+struct Ahead {
+    thing: &'static str,
+    dangerous: bool,
+    distance: usize, // In moves, so 1 is the very next cell
+}
+
+impl Ahead {
+    fn describe(&self) -> String {
+        let cells = match self.distance {
+            1 => "1 cell".to_string(),
+            distance => format!("{} cells", distance),
+        };
+
+        if self.dangerous {
+            format!("{} in {}", self.thing, cells)
+        } else {
+            format!("{} {} ahead", self.thing, cells)
+        }
+    }
+}
+
+impl Default for ScreenReaderFrontend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScreenReaderFrontend {
+    pub fn new() -> Self {
+        Self {
+            stdout: io::stdout(),
+            screen: Vec::new(),
+            ahead: None,
+            direction: None,
+            apple_wanted: false,
+        }
+    }
+
+    /// Say what's ahead whenever the snake turns, something new comes into view, or a danger gets closer
+    fn describe_surroundings(
+        &mut self,
+        game: &GameSnapshot,
+        snake: &SnakeBody,
+        events: &[GameEvent],
+    ) -> Vec<String> {
+        let mut lines = vec![];
+        let direction = *snake.snake_get_direction();
+        let ahead = look_ahead(game, snake);
+        let turned = self.direction != Some(direction);
+        let ate_apple = events
+            .iter()
+            .any(|event| matches!(event, GameEvent::AppleEaten(PLAYER_ONE)));
+        let changed = match (self.ahead, ahead) {
+            (Some(previous), Some(ahead)) => {
+                previous.thing != ahead.thing
+                    || (ahead.dangerous
+                        && ahead.distance <= WARNING_DISTANCE
+                        && previous.distance != ahead.distance)
+            }
+            (None, None) => false,
+            _ => true,
+        };
+
+        if turned || changed || ate_apple {
+            let what = ahead.map_or("nothing ahead".to_string(), |ahead| ahead.describe());
+
+            lines.push(match turned {
+                true => format!("Heading {}, {}.", direction_name(direction), what),
+                false => format!("{}{}.", what[..1].to_uppercase(), &what[1..]),
+            });
+        }
+
+        // Apples only show up on the first tick, so this waits until there's one to point to
+        self.apple_wanted |= turned || ate_apple;
+
+        if self.apple_wanted {
+            let apple_ahead = ahead.is_some_and(|ahead| ahead.thing == "apple");
+            let nearest = nearest_apple(game, snake);
+
+            if apple_ahead || nearest.is_some() {
+                lines.extend(nearest.filter(|_| !apple_ahead));
+                self.apple_wanted = false;
+            }
+        }
+
+        self.ahead = ahead;
+        self.direction = Some(direction);
+
+        lines
+    }
+}
+
+impl Frontend for ScreenReaderFrontend {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let game = &frame.game;
+        let mut lines = vec![];
+        let screen = describe_screen(frame);
+
+        // Only read out the lines that changed, so moving through a menu just reads the new choice
+        for (index, line) in screen.iter().enumerate() {
+            if self.screen.get(index) != Some(line) {
+                lines.push(line.clone());
+            }
+        }
+
+        self.screen = screen;
+
+        for event in &frame.events {
+            lines.push(describe_event(*event, game));
+        }
+
+        let snake = game.snake(PLAYER_ONE);
+
+        if let (GameState::Playing, Some(snake)) = (&game.state, snake) {
+            lines.extend(self.describe_surroundings(game, snake, &frame.events));
+        } else {
+            self.ahead = None;
+            self.direction = None;
+        }
+
+        // Raw mode doesn't move back to the start of the line by itself
+        for line in lines {
+            write!(self.stdout, "{}\r\n", line)?;
+        }
+
+        self.stdout.flush()
+    }
+}
+
+fn describe_screen(frame: &Frame) -> Vec<String> {
+    let game = &frame.game;
+    let keymap = &game.config.keymap;
+
+    match &game.state {
+        GameState::Intro => vec![
+            "Welcome to Snake!".to_string(),
+            format!(
+                "Turn with {}, {}, {} and {}. Eat apples to grow, and keep away from the walls, the obstacles and your own body.",
+                keymap.describe(Action::TurnUp),
+                keymap.describe(Action::TurnLeft),
+                keymap.describe(Action::TurnDown),
+                keymap.describe(Action::TurnRight)
+            ),
+            format!(
+                "Press {} to start, {} to pause, [L] for the leaderboard, or {} to quit.",
+                keymap.describe(Action::Start),
+                keymap.describe(Action::Pause),
+                keymap.describe(Action::Quit)
+            ),
+            format!(
+                "Press [V] for two players on one keyboard, player two turns with {}, {}, {} and {}.",
+                keymap.describe(Action::PlayerTwoUp),
+                keymap.describe(Action::PlayerTwoLeft),
+                keymap.describe(Action::PlayerTwoDown),
+                keymap.describe(Action::PlayerTwoRight)
+            ),
+        ],
+        GameState::Playing if game.playback => vec!["Watching the replay.".to_string()],
+        GameState::Playing => vec!["Playing.".to_string()],
+        GameState::Paused {
+            showing_settings: true,
+            ..
+        } => vec![
+            "Settings.".to_string(),
+            format!(
+                "Map size {} by {}, tick speed {} ms, {} apples.",
+                game.config.width, game.config.height, game.config.tick_speed, game.config.apple_count
+            ),
+            "Press escape to go back.".to_string(),
+        ],
+        GameState::Paused { selected, .. } => vec![
+            "Paused. Choose with up and down, then press enter.".to_string(),
+            format!("{} selected.", selected.label()),
+        ],
+        GameState::Leaderboard => {
+            let mut lines = vec!["Leaderboard.".to_string()];
+
+            if frame.leaderboard.is_empty() {
+                lines.push("No scores yet, go and set one!".to_string());
+            }
+
+            for (position, entry) in frame.leaderboard.iter().enumerate() {
+                lines.push(format!(
+                    "{}. {}, {} points on a {} by {} map.",
+                    position + 1,
+                    entry.name,
+                    entry.score,
+                    entry.playfield_x,
+                    entry.playfield_y
+                ));
+            }
+
+            lines.push("Press L or escape to go back.".to_string());
+
+            lines
+        }
+        GameState::GameOver {
+            score,
+            message,
+            player_name,
+            outcome,
+        } => {
+            let mut lines = match outcome {
+                Some(VersusOutcome::Winner(id)) => vec![format!("{} wins!", player_label(*id))],
+                Some(VersusOutcome::Draw) => vec!["It's a draw!".to_string()],
+                None => vec![],
+            };
+
+            lines.push(message.clone());
+
+            match outcome {
+                Some(_) => lines.extend(game.snakes.iter().map(|snake| {
+                    format!(
+                        "{}: {} points.",
+                        player_label(snake.get_id()),
+                        snake.get_score()
+                    )
+                })),
+                None => lines.push(format!("Final score: {}.", score)),
+            }
+
+            if let Some(player_name) = player_name {
+                lines.push(
+                    "New high score! Type your name and press enter, or escape to skip."
+                        .to_string(),
+                );
+                lines.push(format!("Name: {}", player_name));
+            }
+
+            if let Some(error) = &frame.leaderboard_error {
+                lines.push(error.clone());
+            }
+
+            if game.playback {
+                lines.push("Replay finished.".to_string());
+            } else if let Some(status) = &frame.replay_status {
+                lines.push(status.clone());
+            }
+
+            if player_name.is_none() {
+                lines.push(format!(
+                    "Press {} to go back to the main menu.",
+                    keymap.describe(Action::Restart)
+                ));
+            }
+
+            lines
+        }
+    }
+}
+
+/// In versus, anything that happened to a snake is put down to the player it belongs to
+fn describe_event(event: GameEvent, game: &GameSnapshot) -> String {
+    let description = match event {
+        GameEvent::AppleEaten(id) => {
+            let score = match game.mode {
+                GameMode::Classic => game.score,
+                GameMode::Versus => game.snake(id).map_or(0, SnakeBody::get_score),
+            };
+
+            format!("Apple eaten, score {}.", score)
+        }
+        GameEvent::PowerupPickedUp(_, PowerupType::Supersnake { tick_duration }) => format!(
+            "Supersnake picked up, {} ticks. Obstacles and your body can't hurt you.",
+            tick_duration
+        ),
+        GameEvent::PowerupPickedUp(_, PowerupType::Slowdown { tick_duration }) => {
+            format!("Slowdown picked up, {} ticks.", tick_duration)
+        }
+        GameEvent::PowerupPickedUp(_, PowerupType::MapWrap { tick_duration }) => format!(
+            "Map wrap picked up, {} ticks. The walls take you to the other side.",
+            tick_duration
+        ),
+        GameEvent::PowerupPickedUp(_, PowerupType::None) => "Powerup picked up.".to_string(),
+        GameEvent::PowerupRanOut(_) => "Powerup ran out.".to_string(),
+        GameEvent::MapGrown => {
+            let (playfield_x, playfield_y) = game.playfield_size;
+
+            format!("The map grew to {} by {}.", playfield_x, playfield_y)
+        }
+    };
+
+    match (game.mode, event.get_snake()) {
+        (GameMode::Versus, Some(id)) => format!("{}: {}", player_label(id), description),
+        _ => description,
+    }
+}
+
+/// Walk the cells in front of the head until something is in the way, going round the map if it wraps
+fn look_ahead(game: &GameSnapshot, snake: &SnakeBody) -> Option<Ahead> {
+    let (playfield_x, playfield_y) = game.playfield_size;
+    let can_wrap = snake.snake_can_wrap();
+    // Obstacles and the body can't hurt a supersnake, so they aren't worth mentioning
+    let supersnake = snake.is_invincible();
+    let head = *snake.snake_get_head()?;
+    let body = snake.snake_get_body();
+    let direction = *snake.snake_get_direction();
+    let mut position = head;
+
+    for distance in 1..=playfield_x.max(playfield_y) {
+        position = match position.step(direction, game.playfield_size, can_wrap) {
+            Some(position) => position,
+            None => {
+                return Some(Ahead {
+                    thing: "wall",
+                    dangerous: true,
+                    distance,
+                })
+            }
+        };
+
+        if position == head {
+            break;
+        }
+
+        if !supersnake && position.intersects_multiple(&body) {
+            return Some(Ahead {
+                thing: "your body",
+                dangerous: true,
+                distance,
+            });
+        }
+
+        let other_snake = game.snakes.iter().any(|other| {
+            other.get_id() != snake.get_id() && position.intersects_multiple(other.get_snake())
+        });
+
+        if other_snake {
+            return Some(Ahead {
+                thing: "another snake",
+                dangerous: true,
+                distance,
+            });
+        }
+
+        let entity = game
+            .entities
+            .iter()
+            .find(|entity| entity.get_coordinates() == Some(&position));
+        let (thing, dangerous) = match entity {
+            Some(EntityType::Apple { .. }) => ("apple", false),
+            Some(EntityType::SupersnakePwrup { .. }) => ("supersnake powerup", false),
+            Some(EntityType::SlowdownPwrup { .. }) => ("slowdown powerup", false),
+            Some(EntityType::MapWrapPwrup { .. }) => ("map wrap powerup", false),
+            Some(EntityType::MapGrowPwrup { .. }) => ("map grow powerup", false),
+            Some(EntityType::Obstacle { .. }) if !supersnake => ("obstacle", true),
+            _ => continue,
+        };
+
+        return Some(Ahead {
+            thing,
+            dangerous,
+            distance,
+        });
+    }
+
+    None
+}
+
+/// Say how far the closest apple is across and down from the head
+fn nearest_apple(game: &GameSnapshot, snake: &SnakeBody) -> Option<String> {
+    let head = snake.snake_get_head()?;
+    let apple = game
+        .entities
+        .iter()
+        .filter(|entity| matches!(entity, EntityType::Apple { .. }))
+        .filter_map(|entity| entity.get_coordinates())
+        .min_by_key(|apple| apple.x.abs_diff(head.x) + apple.y.abs_diff(head.y))?;
+    let mut directions = vec![];
+
+    if apple.x > head.x {
+        directions.push(format!("{} right", apple.x - head.x));
+    } else if apple.x < head.x {
+        directions.push(format!("{} left", head.x - apple.x));
+    }
+
+    if apple.y > head.y {
+        directions.push(format!("{} down", apple.y - head.y));
+    } else if apple.y < head.y {
+        directions.push(format!("{} up", head.y - apple.y));
+    }
+
+    Some(format!("Nearest apple {}.", directions.join(", ")))
+}
+
+fn direction_name(direction: SnakeDirection) -> &'static str {
+    match direction {
+        SnakeDirection::Up => "up",
+        SnakeDirection::Down => "down",
+        SnakeDirection::Left => "left",
+        SnakeDirection::Right => "right",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::GameConfig, coordinates::Coordinates, game::Game};
+
+    #[test]
+    fn look_ahead_finds_the_first_thing_in_the_way() {
+        let config = GameConfig {
+            width: 10,
+            height: 10,
+            ..GameConfig::default()
+        };
+        let game = GameSnapshot {
+            entities: vec![],
+            ..Game::new(config, 1).snapshot()
+        };
+        let mut snake = SnakeBody::new(
+            PLAYER_ONE,
+            vec![Coordinates::new(0, 0)],
+            SnakeDirection::Right,
+            2,
+        );
+        let thing_ahead = |game: &GameSnapshot, snake: &SnakeBody| {
+            look_ahead(game, snake).map(|ahead| (ahead.thing, ahead.distance))
+        };
+
+        assert_eq!(thing_ahead(&game, &snake), Some(("wall", 10)));
+
+        let with_apple = GameSnapshot {
+            entities: vec![EntityType::new_apple(Coordinates::new(4, 0))],
+            ..game.clone()
+        };
+
+        assert_eq!(thing_ahead(&with_apple, &snake), Some(("apple", 4)));
+
+        // With nothing in the row, a wrapping snake only has itself to run into
+        snake.set_powerup(PowerupType::MapWrap { tick_duration: 10 });
+
+        assert_eq!(thing_ahead(&game, &snake), None);
+    }
+}
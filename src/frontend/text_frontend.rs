@@ -0,0 +1,526 @@
+use std::io;
+
+use crossterm::terminal;
+
+use crate::{
+    canvas::{Canvas, Cell},
+    clock::TickTiming,
+    color::ColorMode,
+    config::GameConfig,
+    game::{
+        player_label, Characters, EntityType, GameMode, GameSnapshot, GameState, PauseMenuItem,
+        PowerupType, Snake, VersusOutcome, PLAYER_ONE,
+    },
+    keymap::Action,
+    layout::{required_terminal_size, CELL_WIDTH},
+    renderer::Renderer,
+};
+
+use super::{frame::Frame, theme::Theme, traits::Frontend};
+
+/// Draws frames as text in the terminal
+pub struct TextFrontend {
+    renderer: Renderer,
+    theme: Theme,
+}
+
+impl TextFrontend {
+    pub fn new(theme: Theme, color_mode: ColorMode) -> Self {
+        Self {
+            renderer: Renderer::new(color_mode),
+            theme,
+        }
+    }
+}
+
+impl Frontend for TextFrontend {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        if frame.redraw {
+            self.renderer.invalidate();
+        }
+
+        if frame.next_palette {
+            self.theme.palette = self.theme.palette.next();
+        }
+
+        let mut canvas = draw_frame(frame, &self.theme);
+
+        // Long lines, like the intro's, would otherwise run off a narrow terminal
+        if let Ok((columns, _)) = terminal::size() {
+            canvas.wrap(columns as usize);
+        }
+
+        self.renderer.render(&canvas)
+    }
+}
+
+/// Lay out the whole screen for the frame
+pub fn draw_frame(frame: &Frame, theme: &Theme) -> Canvas {
+    let game = &frame.game;
+    let keymap = &game.config.keymap;
+
+    match &game.state {
+        _ if game.suspended => {
+            let mut canvas = Canvas::new();
+            let (playfield_x, playfield_y) = game.playfield_size;
+            let (columns, rows) = required_terminal_size(playfield_x, playfield_y);
+
+            canvas.add_row_string("Terminal too small!");
+            canvas.add_row_string(format!("Resize to at least {}x{}", columns, rows));
+            canvas.add_row_string("to carry on.");
+
+            canvas
+        }
+        GameState::Playing if frame.demo => {
+            let mut canvas = draw_playfield(game, frame.tick_timing, theme);
+            let lines = [
+                "DEMO".to_string(),
+                String::new(),
+                format!("Press {} to play", keymap.describe(Action::Start)),
+            ];
+
+            // Drawn over the map rather than under the HUD, which would need more rows than the terminal was sized for
+            overlay_box(&mut canvas, game.playfield_size, &lines);
+
+            canvas
+        }
+        GameState::Playing => draw_playfield(game, frame.tick_timing, theme),
+        GameState::Intro => {
+            let mut canvas = Canvas::new();
+
+            canvas.add_row_string("Welcome to Snake!");
+            canvas.add_row_string(format!(
+                "{}{}{}",
+                theme.glyph(Characters::SnakeBody),
+                theme.glyph(Characters::SnakeBody),
+                theme.glyph(Characters::SnakeHead)
+            ));
+            canvas.add_row_string(format!(
+                "Use {}, {}, {} and {} to move.",
+                keymap.describe(Action::TurnUp),
+                keymap.describe(Action::TurnLeft),
+                keymap.describe(Action::TurnDown),
+                keymap.describe(Action::TurnRight)
+            ));
+            canvas.add_row_string("");
+            canvas.add_row_string(format!("{} - Eat to grow.", theme.glyph(Characters::Apple)));
+            canvas.add_row_string(format!(
+                "{} - Eat to become invincible to obstacles.",
+                theme.glyph(Characters::SupersnakePwrup)
+            ));
+            canvas.add_row_string(format!("{} - Eat to slow down time, but if you spam-press the arrow key they corresponds to your current direction time speeds up!", theme.glyph(Characters::SlowdownPwrup)));
+            canvas.add_row_string(format!(
+                "{} - Eat to wrap around the edges of the map instead of hitting them.",
+                theme.glyph(Characters::MapWrapPwrup)
+            ));
+            canvas.add_row_string(format!(
+                "{} - Eat to make the map a little bigger, for good.",
+                theme.glyph(Characters::MapGrowPwrup)
+            ));
+            canvas.add_row_string(format!(
+                "{} - Avoid or game over!",
+                theme.glyph(Characters::Obstacle)
+            ));
+            canvas.add_row_string("");
+            canvas.add_row_string(format!(
+                "Press {} to start, and {} to pause.",
+                keymap.describe(Action::Start),
+                keymap.describe(Action::Pause)
+            ));
+            canvas.add_row_string(format!(
+                "Press [V] for two players, player two uses {}, {}, {} and {}.",
+                keymap.describe(Action::PlayerTwoUp),
+                keymap.describe(Action::PlayerTwoLeft),
+                keymap.describe(Action::PlayerTwoDown),
+                keymap.describe(Action::PlayerTwoRight)
+            ));
+            canvas.add_row_string("Press [L] to see the leaderboard.");
+            canvas.add_row_string(format!(
+                "Press [T] to change the colours, now: {}.",
+                theme.palette
+            ));
+            canvas.add_row_string(format!(
+                "You can quit at any time by pressing {} in this screen.",
+                keymap.describe(Action::Quit)
+            ));
+
+            canvas
+        }
+        GameState::Paused {
+            showing_settings: true,
+            ..
+        } => draw_settings(&game.config, theme),
+        GameState::Paused { selected, .. } => {
+            let mut canvas = draw_playfield(game, frame.tick_timing, theme);
+            let mut menu = vec!["PAUSED".to_string(), String::new()];
+
+            for item in PauseMenuItem::ALL {
+                let cursor = if item == *selected { '>' } else { ' ' };
+
+                menu.push(format!("{} {}", cursor, item.label()));
+            }
+
+            overlay_box(&mut canvas, game.playfield_size, &menu);
+
+            canvas
+        }
+        GameState::Leaderboard => {
+            let mut canvas = Canvas::new();
+
+            canvas.add_row_string("Leaderboard");
+            canvas.add_row_string("");
+
+            if frame.leaderboard.is_empty() {
+                canvas.add_row_string("No scores yet, go and set one!");
+            }
+
+            for (position, entry) in frame.leaderboard.iter().enumerate() {
+                let duration = entry.get_duration().as_secs();
+
+                canvas.add_row_string(format!(
+                    "{:>2}. {:<16} {:>5}  {}  {}x{}  {}  {}m{:02}s",
+                    position + 1,
+                    entry.name,
+                    entry.score,
+                    entry.date,
+                    entry.playfield_x,
+                    entry.playfield_y,
+                    entry.mode,
+                    duration / 60,
+                    duration % 60
+                ));
+            }
+
+            canvas.add_row_string("");
+            canvas.add_row_string("Press [L] or [ESC] to go back.");
+
+            canvas
+        }
+        GameState::GameOver {
+            score,
+            message,
+            player_name,
+            outcome,
+        } => {
+            let mut canvas = Canvas::new();
+
+            match outcome {
+                Some(VersusOutcome::Winner(id)) => {
+                    canvas.add_row_string(format!("{} wins!", player_label(*id)))
+                }
+                Some(VersusOutcome::Draw) => canvas.add_row_string("It's a draw!"),
+                None => (),
+            }
+
+            canvas.add_row_string(message);
+
+            match outcome {
+                Some(_) => {
+                    for snake in &game.snakes {
+                        canvas.add_row_string(format!(
+                            "{}: {} points",
+                            player_label(snake.get_id()),
+                            snake.get_score()
+                        ));
+                    }
+                }
+                None => canvas.add_row_string(format!("Final score: {}", score)),
+            }
+
+            if let Some(player_name) = player_name {
+                canvas.add_row_string("");
+                canvas.add_row_string(
+                    "New high score! Type your name and press [ENTER], or [ESC] to skip.",
+                );
+                canvas.add_row_string(format!("Name: {}_", player_name));
+                canvas.add_row_string("");
+            }
+
+            if let Some(error) = &frame.leaderboard_error {
+                canvas.add_row_string(error);
+            }
+
+            canvas.add_row_string(format!("Seed: {}", game.seed));
+
+            if game.playback {
+                canvas.add_row_string("Replay finished.");
+            } else if let Some(status) = &frame.replay_status {
+                canvas.add_row_string(status.as_str());
+            }
+
+            if player_name.is_none() {
+                canvas.add_row_string(format!(
+                    "Press {} to go back to the main menu.",
+                    keymap.describe(Action::Restart)
+                ));
+            }
+
+            canvas
+        }
+    }
+}
+
+/// Draw the map with the snake and entities on it, followed by the HUD rows
+pub fn draw_playfield(
+    game: &GameSnapshot,
+    tick_timing: Option<TickTiming>,
+    theme: &Theme,
+) -> Canvas {
+    let mut canvas = Canvas::new();
+
+    let speed_display = match tick_timing {
+        Some(tick_timing) => format!(
+            "Tick speed (ms): {} (actual {:.1} ±{:.1})",
+            game.tick_speed,
+            tick_timing.measured.as_secs_f64() * 1000.0,
+            tick_timing.jitter.as_secs_f64() * 1000.0
+        ),
+        None => format!("Tick speed (ms): {}", game.tick_speed),
+    };
+
+    let (playfield_x, playfield_y) = game.playfield_size;
+    let size_display = format!(
+        "Map size: {}x{} (max {}x{})",
+        playfield_x, playfield_y, game.config.max_width, game.config.max_height
+    );
+
+    canvas.fill(
+        theme.cell(Characters::Grass),
+        playfield_x,
+        playfield_y,
+        CELL_WIDTH,
+    );
+
+    match game.mode {
+        GameMode::Classic => {
+            let powerup = game
+                .snake(PLAYER_ONE)
+                .map_or(PowerupType::None, |snake| *snake.get_powerup());
+
+            canvas.add_row_string(format!("Score: {}", game.score));
+            canvas.add_row_string(speed_display);
+            canvas.add_row_string(size_display);
+            canvas.add_row_string(format!("Powerup: {}", describe_powerup(powerup)));
+        }
+        // Each player gets a row with their own score and powerup
+        GameMode::Versus => {
+            for snake in &game.snakes {
+                canvas.add_row_string(format!(
+                    "{}: {} points, powerup: {}",
+                    player_label(snake.get_id()),
+                    snake.get_score(),
+                    describe_powerup(*snake.get_powerup())
+                ));
+            }
+
+            canvas.add_row_string(speed_display);
+            canvas.add_row_string(size_display);
+        }
+    }
+
+    for entity in &game.entities {
+        let character = match entity {
+            EntityType::SupersnakePwrup { .. } => Characters::SupersnakePwrup,
+            EntityType::SlowdownPwrup { .. } => Characters::SlowdownPwrup,
+            EntityType::Apple { .. } => Characters::Apple,
+            EntityType::MapWrapPwrup { .. } => Characters::MapWrapPwrup,
+            EntityType::MapGrowPwrup { .. } => Characters::MapGrowPwrup,
+            EntityType::Obstacle { .. } => Characters::Obstacle,
+        };
+
+        canvas.set_coord(entity.get_coordinates().unwrap(), theme.cell(character));
+    }
+
+    // Player two's snake has its own look, but takes on the same body as player one's for a powerup
+    for snake in &game.snakes {
+        let (head_character, plain_body) = match snake.get_id() {
+            PLAYER_ONE => (Characters::SnakeHead, Characters::SnakeBody),
+            _ => (Characters::RivalHead, Characters::RivalBody),
+        };
+        let body_character = match snake.get_powerup() {
+            PowerupType::Supersnake { .. } => Characters::SnakeBodySuper,
+            PowerupType::Slowdown { .. } => Characters::SnakeBodySlow,
+            PowerupType::MapWrap { .. } => Characters::SnakeBodyWrap,
+            PowerupType::None => plain_body,
+        };
+
+        for coordinate in snake.snake_get_body() {
+            canvas.set_coord(&coordinate, theme.cell(body_character));
+        }
+
+        if let Some(snake_head) = snake.snake_get_head() {
+            canvas.set_coord(snake_head, theme.cell(head_character));
+        }
+    }
+
+    canvas
+}
+
+/// Spelled out as well as shown on the snake, so the powerup can be told without going by colour
+fn describe_powerup(powerup: PowerupType) -> String {
+    match powerup {
+        PowerupType::Supersnake { tick_duration } => {
+            format!("Supersnake, {} ticks left", tick_duration)
+        }
+        PowerupType::Slowdown { tick_duration } => {
+            format!("Slowdown, {} ticks left", tick_duration)
+        }
+        PowerupType::MapWrap { tick_duration } => {
+            format!("Map wrap, {} ticks left", tick_duration)
+        }
+        PowerupType::None => "none".to_string(),
+    }
+}
+
+/// Draw the lines in a box across the middle of the map, leaving the map showing either side of it
+fn overlay_box(canvas: &mut Canvas, playfield: (usize, usize), lines: &[String]) {
+    let (playfield_x, playfield_y) = playfield;
+    let inner_width = lines.iter().map(|line| line.len()).max().unwrap_or(0) + 2;
+    let border = format!("+{}+", "-".repeat(inner_width));
+
+    let mut rows = vec![border.clone()];
+    rows.extend(
+        lines
+            .iter()
+            .map(|line| format!("| {:<width$}|", line, width = inner_width - 1)),
+    );
+    rows.push(border);
+
+    let row_columns = playfield_x * CELL_WIDTH;
+    let box_columns = (inner_width + 2).min(row_columns);
+    let left_cells = (row_columns - box_columns) / 2 / CELL_WIDTH;
+    let right_columns = row_columns - left_cells * CELL_WIDTH - box_columns;
+    let right_cells = right_columns / CELL_WIDTH;
+    let top = playfield_y.saturating_sub(rows.len()) / 2;
+
+    for (offset, text) in rows.iter().enumerate() {
+        let y = top + offset;
+
+        if y >= playfield_y {
+            break;
+        }
+
+        let existing = canvas.get_rows()[y].clone();
+        let mut row = existing[..left_cells].to_vec();
+
+        row.extend(text.chars().take(box_columns).map(Cell::from));
+        // Cells are wider than the box's characters, so an odd gap is made up with a space
+        row.extend(std::iter::repeat_n(
+            Cell::from(' '),
+            right_columns % CELL_WIDTH,
+        ));
+        row.extend_from_slice(&existing[playfield_x - right_cells..]);
+
+        canvas.set_row(y, row);
+    }
+}
+
+fn draw_settings(config: &GameConfig, theme: &Theme) -> Canvas {
+    let mut canvas = Canvas::new();
+
+    canvas.add_row_string("Settings");
+    canvas.add_row_string("");
+    canvas.add_row_string(format!("Map size: {}x{}", config.width, config.height));
+    canvas.add_row_string(format!(
+        "Maximum map size: {}x{}",
+        config.max_width, config.max_height
+    ));
+    canvas.add_row_string(format!("Tick speed (ms): {}", config.tick_speed));
+    canvas.add_row_string(format!(
+        "Slowdown penalty (ms): {}",
+        config.slowdown_penalty
+    ));
+    canvas.add_row_string(format!("Apples: {}", config.apple_count));
+    canvas.add_row_string(format!(
+        "Supersnake: lasts {} ticks, every {} points",
+        config.supersnake_duration, config.supersnake_every
+    ));
+    canvas.add_row_string(format!(
+        "Slowdown: lasts {} ticks, every {} points",
+        config.slowdown_duration, config.slowdown_every
+    ));
+    canvas.add_row_string(format!(
+        "Map wrap: lasts {} ticks, every {} points",
+        config.map_wrap_duration, config.map_wrap_every
+    ));
+    canvas.add_row_string(format!("Map grow: every {} points", config.map_grow_every));
+    canvas.add_row_string(format!("Glyphs: {}", theme.glyphs));
+    canvas.add_row_string(format!("Colours: {}", theme.palette));
+    canvas.add_row_string("");
+
+    if let Some(path) = GameConfig::default_path() {
+        canvas.add_row_string(format!("Change these in {}", path.display()));
+        canvas.add_row_string("or with command line flags, then start a new game.");
+    }
+
+    canvas.add_row_string("");
+    canvas.add_row_string("Press [ESC] to go back.");
+
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coordinates::Coordinates,
+        frontend::ColorTheme,
+        game::{Entity, GlyphTheme, Palette, Simulation},
+    };
+
+    #[test]
+    fn entities_render_at_their_cell_on_non_square_maps() {
+        let config = GameConfig {
+            width: 30,
+            height: 12,
+            max_width: 30,
+            max_height: 12,
+            ..GameConfig::default()
+        };
+        let mut simulation = Simulation::new(config, 1);
+
+        simulation.advance(3);
+
+        let game = simulation.game();
+
+        for glyphs in [GlyphTheme::Emoji, GlyphTheme::Unicode, GlyphTheme::Ascii] {
+            for palette in Palette::ALL {
+                let theme = Theme {
+                    glyphs,
+                    colors: ColorTheme::default(),
+                    palette,
+                };
+                let canvas = draw_playfield(&game.snapshot(), None, &theme);
+                // Narrow glyphs are padded out to the cell width, which isn't part of the glyph itself
+                let glyph_at = |x, y| {
+                    canvas
+                        .get_coord(&Coordinates::new(x, y))
+                        .map(|cell| cell.glyph.trim_end())
+                };
+
+                for entity in game.get_all_entities() {
+                    let (x, y) = entity.get_coordinates().unwrap().as_tuple();
+                    let expected = match entity {
+                        EntityType::Apple { .. } => theme.glyph(Characters::Apple),
+                        EntityType::Obstacle { .. } => theme.glyph(Characters::Obstacle),
+                        _ => continue,
+                    };
+
+                    assert_eq!(glyph_at(x, y), Some(expected));
+                }
+
+                // The bottom right obstacle sits relative to the far corner, so it only lines up if rows and columns aren't swapped
+                let obstacle = theme.glyph(Characters::Obstacle);
+                assert_eq!(glyph_at(24, 6), Some(obstacle));
+                assert_eq!(glyph_at(3, 0), Some(theme.glyph(Characters::SnakeHead)));
+                assert_eq!(glyph_at(29, 11), Some(theme.glyph(Characters::Grass)));
+                assert_eq!(glyph_at(30, 0), None);
+
+                // Every theme has to keep the map rows the same width, or the columns stop lining up
+                for row in &canvas.get_rows()[..12] {
+                    assert_eq!(row.iter().map(Cell::width).sum::<usize>(), 30 * CELL_WIDTH);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use thiserror::Error;
+
+use crate::{
+    canvas::Cell,
+    color::Style,
+    game::{Characters, GlyphTheme, Palette},
+};
+
+#[derive(Error, Debug)]
+pub enum ThemeError {
+    #[error("Could not access the theme file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read the theme file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// Colours from a theme file, drawn instead of the palette's colours for the characters it mentions
+#[derive(Clone, Default)]
+pub struct ColorTheme {
+    styles: HashMap<Characters, Style>,
+}
+
+impl ColorTheme {
+    /// Load a theme file. Each character it mentions gets the colours given for it, for example:
+    ///
+    /// ```toml
+    /// [snake_body]
+    /// fg = "#3cc83c"
+    /// bg = "#103010"
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        Ok(Self {
+            styles: toml::from_str(&fs::read_to_string(path)?)?,
+        })
+    }
+
+    pub fn style(&self, character: Characters) -> Option<Style> {
+        self.styles.get(&character).copied()
+    }
+}
+
+/// How the game looks: which glyphs it is drawn with, the palette, and any colours that replace the palette's
+#[derive(Clone)]
+pub struct Theme {
+    pub glyphs: GlyphTheme,
+    pub colors: ColorTheme,
+    pub palette: Palette,
+}
+
+impl Theme {
+    pub fn glyph(&self, character: Characters) -> &'static str {
+        if self.palette.distinct_shapes() {
+            character.shaped_value(self.glyphs)
+        } else {
+            character.value(self.glyphs)
+        }
+    }
+
+    pub fn cell(&self, character: Characters) -> Cell {
+        let style = self
+            .colors
+            .style(character)
+            .unwrap_or_else(|| self.palette.style(character));
+
+        Cell::new(self.glyph(character), style)
+    }
+}
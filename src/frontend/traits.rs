@@ -0,0 +1,9 @@
+use std::io;
+
+use super::frame::Frame;
+
+/// Something that shows the game, like the terminal, a spectator or a recorder. Frontends only ever see frames,
+/// so they can be added without touching the game rules.
+pub trait Frontend: Send {
+    fn draw(&mut self, frame: &Frame) -> io::Result<()>;
+}
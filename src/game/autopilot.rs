@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::coordinates::Coordinates;
+
+use super::{
+    entity_type::EntityType,
+    snake_body::SnakeId,
+    snapshot::GameSnapshot,
+    traits::{Snake, SnakeDirection},
+};
+
+const DIRECTIONS: [SnakeDirection; 4] = [
+    SnakeDirection::Up,
+    SnakeDirection::Down,
+    SnakeDirection::Left,
+    SnakeDirection::Right,
+];
+
+/// Pick the snake's next turn without a player. It takes the shortest way to the nearest apple, as long as it could
+/// still reach its own tail after eating it. Otherwise it follows its tail, which keeps a way out open as the tail
+/// moves on, and failing that heads for the most room. Returns None to carry on the way the snake is going.
+///
+/// The bot plays it safe: it never counts on a powerup lasting, so walls are always walls and obstacles always hurt.
+pub fn autopilot_turn(game: &GameSnapshot, id: SnakeId) -> Option<SnakeDirection> {
+    let snake = game.snake(id)?;
+    let body = snake.get_snake();
+    let heading = *snake.snake_get_direction();
+    let board = Board::new(game, id);
+    let apples: HashSet<Coordinates> = game
+        .entities
+        .iter()
+        .filter(|entity| matches!(entity, EntityType::Apple { .. }))
+        .filter_map(|entity| entity.get_coordinates().copied())
+        .collect();
+
+    let to_apple = board
+        .path(body, heading, |cell| apples.contains(&cell))
+        .filter(|path| {
+            let fed = follow(body, path, true);
+
+            board.reaches_tail(
+                &fed,
+                board.direction(fed[fed.len() - 2], fed[fed.len() - 1]),
+            )
+        })
+        .map(|path| path[0]);
+
+    let next = to_apple
+        .or_else(|| board.towards_tail(body, heading))
+        .or_else(|| board.roomiest(body, heading))?;
+    let direction = board.direction(*body.last()?, next);
+
+    (direction != heading).then_some(direction)
+}
+
+/// The map as the bot sees it
+struct Board {
+    playfield: (usize, usize),
+    blocked: HashSet<Coordinates>, // Obstacles and other snakes, which don't move out of the way as the bot plans
+}
+
+impl Board {
+    fn new(game: &GameSnapshot, id: SnakeId) -> Self {
+        let mut blocked: HashSet<Coordinates> = game
+            .entities
+            .iter()
+            .filter(|entity| matches!(entity, EntityType::Obstacle { .. }))
+            .filter_map(|entity| entity.get_coordinates().copied())
+            .collect();
+
+        for other in &game.snakes {
+            if other.get_id() != id {
+                blocked.extend(other.get_snake());
+            }
+        }
+
+        Self {
+            playfield: game.playfield_size,
+            blocked,
+        }
+    }
+
+    /// The way to go to get from one cell to the next one along
+    fn direction(&self, from: Coordinates, to: Coordinates) -> SnakeDirection {
+        DIRECTIONS
+            .into_iter()
+            .find(|direction| from.step(*direction, self.playfield, false) == Some(to))
+            .unwrap_or(SnakeDirection::Right)
+    }
+
+    /// Breadth first search from the head, given from the tail to the head, to the nearest cell the goal accepts.
+    /// Returns the cells on the way, ending with the goal. The goal is checked before the body is, so the snake can
+    /// aim for its own tail.
+    fn path(
+        &self,
+        body: &[Coordinates],
+        heading: SnakeDirection,
+        goal: impl Fn(Coordinates) -> bool,
+    ) -> Option<Vec<Coordinates>> {
+        let head = *body.last()?;
+        let body: HashSet<&Coordinates> = body.iter().collect();
+        let mut came_from: HashMap<Coordinates, Coordinates> = HashMap::new();
+        let mut queue = VecDeque::from([head]);
+
+        while let Some(cell) = queue.pop_front() {
+            for direction in DIRECTIONS {
+                // The snake can't turn straight back on itself
+                if cell == head && direction == heading.opposite() {
+                    continue;
+                }
+
+                let Some(next) = cell.step(direction, self.playfield, false) else {
+                    continue;
+                };
+
+                if next == head || came_from.contains_key(&next) {
+                    continue;
+                }
+
+                if goal(next) {
+                    came_from.insert(next, cell);
+
+                    return Some(walk_back(&came_from, head, next));
+                }
+
+                if self.blocked.contains(&next) || body.contains(&next) {
+                    continue;
+                }
+
+                came_from.insert(next, cell);
+                queue.push_back(next);
+            }
+        }
+
+        None
+    }
+
+    fn reaches_tail(&self, body: &[Coordinates], heading: SnakeDirection) -> bool {
+        body.len() < 2 || self.path(body, heading, |cell| cell == body[0]).is_some()
+    }
+
+    /// The first moves the snake can make without crashing straight away
+    fn safe_moves(&self, body: &[Coordinates], heading: SnakeDirection) -> Vec<Coordinates> {
+        let Some(head) = body.last() else {
+            return vec![];
+        };
+
+        DIRECTIONS
+            .into_iter()
+            .filter(|direction| *direction != heading.opposite())
+            .filter_map(|direction| head.step(direction, self.playfield, false))
+            .filter(|cell| !self.blocked.contains(cell) && !body.contains(cell))
+            .collect()
+    }
+
+    /// The move that leaves the tail furthest away while it can still be reached, so the snake coils up slowly
+    fn towards_tail(&self, body: &[Coordinates], heading: SnakeDirection) -> Option<Coordinates> {
+        self.safe_moves(body, heading)
+            .into_iter()
+            .filter_map(|cell| {
+                let moved = follow(body, &[cell], false);
+                let direction = self.direction(*body.last()?, cell);
+                let distance = match moved.len() {
+                    1 => 0,
+                    _ => self.path(&moved, direction, |next| next == moved[0])?.len(),
+                };
+
+                Some((cell, distance))
+            })
+            .max_by_key(|(_, distance)| *distance)
+            .map(|(cell, _)| cell)
+    }
+
+    /// The move with the most cells reachable from it, for when the snake is already boxed in
+    fn roomiest(&self, body: &[Coordinates], heading: SnakeDirection) -> Option<Coordinates> {
+        self.safe_moves(body, heading)
+            .into_iter()
+            .max_by_key(|cell| self.room(&follow(body, &[*cell], false)))
+    }
+
+    /// How many cells the head can still get to
+    fn room(&self, body: &[Coordinates]) -> usize {
+        let Some(head) = body.last() else {
+            return 0;
+        };
+        let mut seen = HashSet::from([*head]);
+        let mut queue = VecDeque::from([*head]);
+
+        while let Some(cell) = queue.pop_front() {
+            for direction in DIRECTIONS {
+                let Some(next) = cell.step(direction, self.playfield, false) else {
+                    continue;
+                };
+
+                if !seen.contains(&next) && !self.blocked.contains(&next) && !body.contains(&next) {
+                    seen.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        seen.len() - 1
+    }
+}
+
+/// Where the body would be after moving along the path, growing on the last cell if it's an apple
+fn follow(body: &[Coordinates], path: &[Coordinates], grows: bool) -> Vec<Coordinates> {
+    let mut body = body.to_vec();
+
+    for (index, cell) in path.iter().enumerate() {
+        body.push(*cell);
+
+        if !(grows && index == path.len() - 1) {
+            body.remove(0);
+        }
+    }
+
+    body
+}
+
+fn walk_back(
+    came_from: &HashMap<Coordinates, Coordinates>,
+    start: Coordinates,
+    end: Coordinates,
+) -> Vec<Coordinates> {
+    let mut path = vec![end];
+    let mut cell = end;
+
+    while let Some(previous) = came_from.get(&cell) {
+        if *previous == start {
+            break;
+        }
+
+        path.push(*previous);
+        cell = *previous;
+    }
+
+    path.reverse();
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::GameConfig,
+        game::{Simulation, PLAYER_ONE},
+    };
+
+    /// A baseline for the bot, and for people to measure themselves against
+    #[test]
+    fn autopilot_keeps_eating_on_the_default_map() {
+        let mut simulation = Simulation::new(GameConfig::default(), 1);
+
+        for _ in 0..500 {
+            if simulation.is_over() {
+                break;
+            }
+
+            let turn = autopilot_turn(&simulation.game().snapshot(), PLAYER_ONE);
+
+            simulation.step(turn.as_slice());
+        }
+
+        assert!(!simulation.is_over());
+        assert!(simulation.game().get_score() >= 40);
+    }
+}
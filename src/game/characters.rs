@@ -1,4 +1,9 @@
-#[derive(Clone, Copy)]
+use serde::Deserialize;
+
+use super::glyph_theme::GlyphTheme;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
 pub enum Characters {
     SnakeHead,
     SnakeBody,
@@ -9,20 +14,89 @@ pub enum Characters {
     SupersnakePwrup,
     SnakeBodySlow,
     SlowdownPwrup,
+    SnakeBodyWrap,
+    MapWrapPwrup,
+    MapGrowPwrup,
+    RivalHead,
+    RivalBody,
 }
 
 impl Characters {
-    pub fn value(&self) -> char {
+    /// The glyph for this character in the theme. Glyphs can be narrower than a map cell, the canvas pads them out.
+    pub fn value(&self, theme: GlyphTheme) -> &'static str {
+        match theme {
+            GlyphTheme::Emoji => self.emoji(),
+            GlyphTheme::Unicode => self.unicode(),
+            GlyphTheme::Ascii => self.ascii(),
+        }
+    }
+
+    /// Like `value`, but snake bodies and obstacles all get their own shape, so none of them are told apart by colour alone.
+    /// The unicode and ascii glyphs already differ in shape.
+    pub fn shaped_value(&self, theme: GlyphTheme) -> &'static str {
+        match (theme, self) {
+            (GlyphTheme::Emoji, Characters::SnakeBodySuper) => "🔷",
+            (GlyphTheme::Emoji, Characters::SnakeBodySlow) => "🟡",
+            (GlyphTheme::Emoji, Characters::SnakeBodyWrap) => "💜",
+            (GlyphTheme::Emoji, Characters::Obstacle) => "🧱",
+            _ => self.value(theme),
+        }
+    }
+
+    fn emoji(&self) -> &'static str {
+        match self {
+            Characters::SnakeHead => "👀",
+            Characters::SnakeBody => "🟩",
+            Characters::SnakeBodySuper => "🟦",
+            Characters::SnakeBodySlow => "🟨",
+            Characters::SnakeBodyWrap => "🟪",
+            Characters::Obstacle => "🟥",
+            Characters::Grass => "➕",
+            Characters::Apple => "🍏",
+            Characters::SupersnakePwrup => "🐍",
+            Characters::SlowdownPwrup => "🐌",
+            Characters::MapWrapPwrup => "🌀",
+            Characters::MapGrowPwrup => "🌱",
+            Characters::RivalHead => "🔶",
+            Characters::RivalBody => "🟧",
+        }
+    }
+
+    fn unicode(&self) -> &'static str {
+        match self {
+            Characters::SnakeHead => "◉◉",
+            Characters::SnakeBody => "██",
+            Characters::SnakeBodySuper => "▓▓",
+            Characters::SnakeBodySlow => "▒▒",
+            Characters::SnakeBodyWrap => "░░",
+            Characters::Obstacle => "╳╳",
+            Characters::Grass => "·",
+            Characters::Apple => "●",
+            Characters::SupersnakePwrup => "★",
+            Characters::SlowdownPwrup => "◷",
+            Characters::MapWrapPwrup => "↻",
+            Characters::MapGrowPwrup => "✚",
+            Characters::RivalHead => "◎◎",
+            Characters::RivalBody => "▚▚",
+        }
+    }
+
+    fn ascii(&self) -> &'static str {
         match self {
-            Characters::SnakeHead => '👀',
-            Characters::SnakeBody => '🟩',
-            Characters::SnakeBodySuper => '🟦',
-            Characters::SnakeBodySlow => '🟨',
-            Characters::Obstacle => '🟥',
-            Characters::Grass => '➕',
-            Characters::Apple => '🍏',
-            Characters::SupersnakePwrup => '🐍',
-            Characters::SlowdownPwrup => '🐌',
+            Characters::SnakeHead => "OO",
+            Characters::SnakeBody => "[]",
+            Characters::SnakeBodySuper => "{}",
+            Characters::SnakeBodySlow => "()",
+            Characters::SnakeBodyWrap => "<>",
+            Characters::Obstacle => "XX",
+            Characters::Grass => ".",
+            Characters::Apple => "*",
+            Characters::SupersnakePwrup => "S",
+            Characters::SlowdownPwrup => "Z",
+            Characters::MapWrapPwrup => "W",
+            Characters::MapGrowPwrup => "G",
+            Characters::RivalHead => "@@",
+            Characters::RivalBody => "##",
         }
     }
 }
@@ -1,52 +1,49 @@
 use crate::coordinates::Coordinates;
 
-use super::Characters;
-
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum EntityType {
-    Apple {
-        coordinates: Option<Coordinates>,
-        emoji: char,
-    },
-    SupersnakePwrup {
-        coordinates: Option<Coordinates>,
-        emoji: char,
-    },
-    Obstacle {
-        coordinates: Option<Coordinates>,
-        emoji: char,
-    },
-    SlowdownPwrup {
-        coordinates: Option<Coordinates>,
-        emoji: char,
-    },
+    Apple { coordinates: Option<Coordinates> },
+    SupersnakePwrup { coordinates: Option<Coordinates> },
+    Obstacle { coordinates: Option<Coordinates> },
+    SlowdownPwrup { coordinates: Option<Coordinates> },
+    MapWrapPwrup { coordinates: Option<Coordinates> },
+    MapGrowPwrup { coordinates: Option<Coordinates> },
 }
 
 impl EntityType {
     pub fn new_apple(coordinates: Coordinates) -> Self {
         Self::Apple {
             coordinates: Some(coordinates),
-            emoji: Characters::Apple.value(),
         }
     }
 
     pub fn new_supersnake(coordinates: Coordinates) -> Self {
         Self::SupersnakePwrup {
             coordinates: Some(coordinates),
-            emoji: Characters::SupersnakePwrup.value(),
         }
     }
 
     pub fn new_obstacle(coordinates: Coordinates) -> Self {
         Self::Obstacle {
             coordinates: Some(coordinates),
-            emoji: Characters::Obstacle.value(),
         }
     }
 
     pub fn new_slowdown(coordinates: Coordinates) -> Self {
         Self::SlowdownPwrup {
             coordinates: Some(coordinates),
-            emoji: Characters::SlowdownPwrup.value(),
+        }
+    }
+
+    pub fn new_map_wrap(coordinates: Coordinates) -> Self {
+        Self::MapWrapPwrup {
+            coordinates: Some(coordinates),
+        }
+    }
+
+    pub fn new_map_grow(coordinates: Coordinates) -> Self {
+        Self::MapGrowPwrup {
+            coordinates: Some(coordinates),
         }
     }
 
@@ -56,6 +53,8 @@ impl EntityType {
             Self::SupersnakePwrup { coordinates, .. } => coordinates.as_ref(),
             Self::Obstacle { coordinates, .. } => coordinates.as_ref(),
             Self::SlowdownPwrup { coordinates, .. } => coordinates.as_ref(),
+            Self::MapWrapPwrup { coordinates, .. } => coordinates.as_ref(),
+            Self::MapGrowPwrup { coordinates, .. } => coordinates.as_ref(),
         }
     }
 }
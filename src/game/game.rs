@@ -1,46 +1,146 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+use tokio::time::Instant;
 
-use crate::coordinates::Coordinates;
+use crate::{config::GameConfig, coordinates::Coordinates};
 
 use super::{
     entity_type::EntityType,
-    game_state::GameState,
+    game_event::GameEvent,
+    game_mode::GameMode,
+    game_state::{GameState, VersusOutcome},
+    pause_menu::PauseMenuItem,
     powerup::PowerupType,
-    traits::{
-        Entity, {Snake, SnakeDirection},
-    },
+    replay::Replay,
+    snake_body::{player_label, SnakeBody, SnakeId, PLAYER_ONE, PLAYER_TWO},
+    snapshot::GameSnapshot,
+    traits::{Entity, Snake, SnakeDirection, SnakeError},
 };
 
+/// Obstacles laid out on the map, a few fewer if they'd land on something
+pub const OBSTACLE_COUNT: usize = 20;
+
+/// What a snake crashed into
+enum Crash {
+    Wall(SnakeError),
+    OwnBody,
+    Obstacle,
+    HeadOn,
+    Into(SnakeId), // The body of another snake
+}
+
+impl Crash {
+    /// The game over message. With one player it's addressed to them, in versus it names whose snake crashed.
+    fn describe(&self, id: SnakeId, mode: GameMode) -> String {
+        let name = player_label(id);
+
+        match (mode, self) {
+            (GameMode::Classic, Crash::Wall(error)) => error.to_string(),
+            (GameMode::Classic, Crash::OwnBody) => "You ate yourself!".to_string(),
+            (GameMode::Classic, Crash::Obstacle) => "You hit an obstacle!".to_string(),
+            (_, Crash::Wall(_)) => format!("{} hit a wall!", name),
+            (_, Crash::OwnBody) => format!("{} ate itself!", name),
+            (_, Crash::Obstacle) => format!("{} hit an obstacle!", name),
+            (_, Crash::HeadOn) => "The snakes crashed head on!".to_string(),
+            (_, Crash::Into(other)) => {
+                format!("{} ran into {}!", name, player_label(*other).to_lowercase())
+            }
+        }
+    }
+}
+
 pub struct Game {
     entities: HashMap<Coordinates, EntityType>,
-    snake: Vec<Coordinates>,
+    snakes: Vec<SnakeBody>,
     score: u64,
     playfield_x: usize,
     playfield_y: usize,
-    current_direction: SnakeDirection, // Only updates next game tick
-    next_direction: Vec<SnakeDirection>, // Queues up for next game tick
     state: GameState,
-    current_powerup: PowerupType,
-    base_tick_speed: u64,
+    config: GameConfig,
+    seed: u64,
+    keeps_seed: bool, // The player picked the seed, so restarting plays it again instead of drawing a new one
+    tick: u64,
+    rng: StdRng, // Every spawn draws from this so a seed always replays the same game
+    recording: Replay,
+    playback: Option<Replay>, // When set, directions come from the replay instead of the player
+    mode: GameMode,
+    started_at: Option<Instant>,
+    stopped_at: Option<Instant>, // While paused or suspended, which doesn't count towards the duration
+    duration: Duration,          // Frozen once the game is over
+    suspended: bool, // Stops the clock, for example while the terminal is too small to draw the map
+    events: Vec<GameEvent>, // Since they were last taken
 }
 
 impl Game {
-    pub fn new(playfield_x: usize, playfield_y: usize) -> Self {
-        Self {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let mut game = Self {
             entities: HashMap::new(),
-            snake: Vec::from([Coordinates::new(0, 0)]),
+            snakes: Self::spawn_snakes(GameMode::Classic, &config),
             score: 0,
-            playfield_x: playfield_x,
-            playfield_y: playfield_y,
-            current_direction: SnakeDirection::Right,
-            next_direction: Vec::from([SnakeDirection::Right]),
+            playfield_x: config.width,
+            playfield_y: config.height,
             state: GameState::Intro,
-            current_powerup: PowerupType::None,
-            base_tick_speed: 200,
+            recording: Replay::new(config.clone(), seed),
+            config,
+            seed,
+            keeps_seed: false,
+            tick: 0,
+            rng: StdRng::seed_from_u64(seed),
+            playback: None,
+            mode: GameMode::Classic,
+            started_at: None,
+            stopped_at: None,
+            duration: Duration::ZERO,
+            suspended: false,
+            events: Vec::new(),
+        };
+
+        game.layout_obstacles();
+
+        game
+    }
+
+    /// Player one starts in the top left corner heading right, and in versus player two starts in the opposite corner
+    fn spawn_snakes(mode: GameMode, config: &GameConfig) -> Vec<SnakeBody> {
+        let mut snakes = vec![SnakeBody::new(
+            PLAYER_ONE,
+            vec![Coordinates::new(0, 0)],
+            SnakeDirection::Right,
+            config.max_queued_turns,
+        )];
+
+        if mode == GameMode::Versus {
+            snakes.push(SnakeBody::new(
+                PLAYER_TWO,
+                vec![Coordinates::new(config.width - 1, config.height - 1)],
+                SnakeDirection::Left,
+                config.max_queued_turns,
+            ));
         }
+
+        snakes
     }
 
+    /// Creates a game that re-simulates the given replay, ignoring any directions set by the player
+    pub fn new_playback(replay: Replay) -> Self {
+        let mut game = Self::new(replay.config.clone(), replay.seed);
+
+        game.set_mode(replay.mode);
+        game.playback = Some(replay);
+
+        game
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> &GameState {
+        if self.suspended {
+            return self.get_state();
+        }
+
         match self.state {
             GameState::Playing => {
                 self.process_next_game_tick();
@@ -48,91 +148,224 @@ impl Game {
             }
             GameState::GameOver { .. } => self.get_state(),
             GameState::Intro => self.get_state(),
+            GameState::Leaderboard => self.get_state(),
+            GameState::Paused { .. } => self.get_state(),
+        }
+    }
+
+    fn end_game(&mut self, message: String, outcome: Option<VersusOutcome>) {
+        if let Some(started_at) = self.started_at {
+            self.duration = started_at.elapsed();
         }
+
+        self.state = GameState::GameOver {
+            score: self.score,
+            message,
+            player_name: None,
+            outcome,
+        };
     }
 
+    /// Every snake moves at once, then anything that crashed ends the game. Only once no snake has crashed
+    /// do they eat what they landed on.
     fn process_next_game_tick(&mut self) -> &GameState {
-        if self.next_direction.len() > 0 {
-            let removed = self.next_direction.remove(0);
+        self.tick += 1;
 
-            self.current_direction = removed;
-        }
+        let previous_heads: Vec<Option<Coordinates>> = self
+            .snakes
+            .iter()
+            .map(|snake| snake.snake_get_head().copied())
+            .collect();
+        let mut crashes: Vec<(SnakeId, Crash)> = vec![];
+
+        for snake in &mut self.snakes {
+            let id = snake.get_id();
+            let turned = match &self.playback {
+                Some(playback) => playback
+                    .direction_at(self.tick, id)
+                    .and_then(|direction| snake.turn_now(direction)),
+                None => snake.take_queued_turn(),
+            };
+
+            if let Some(direction) = turned {
+                self.recording.record(self.tick, id, direction);
+            }
+
+            if let Err(error) = snake.snake_add_head(self.playfield_x, self.playfield_y) {
+                crashes.push((id, Crash::Wall(error)));
+
+                continue;
+            }
+
+            if !snake.is_invincible() && snake.snake_eating_itself() {
+                crashes.push((id, Crash::OwnBody));
+
+                continue;
+            }
 
-        match self.snake_add_head(self.playfield_x, self.playfield_y) {
-            Ok(_) => {}
-            Err(error) => {
-                self.state = GameState::GameOver {
-                    score: self.score,
-                    message: format!("{} press [R] to go back to the main menu.", error),
-                };
+            if snake.wear_off_powerup() {
+                self.events.push(GameEvent::PowerupRanOut(id));
+            }
+
+            let on_obstacle = snake
+                .snake_get_head()
+                .and_then(|head| self.entities.get(head))
+                .is_some_and(|entity| matches!(entity, EntityType::Obstacle { .. }));
 
-                return &self.state;
+            if on_obstacle && !snake.is_invincible() {
+                crashes.push((id, Crash::Obstacle));
             }
         }
 
-        if !matches!(self.current_powerup, PowerupType::Supersnake { .. })
-            && self.snake_eating_itself()
-        {
-            self.state = GameState::GameOver {
-                score: self.score,
-                message: "You ate yourself! Press [R] to restart.".to_string(),
-            };
+        crashes.extend(self.snake_collisions(&previous_heads));
+
+        if !crashes.is_empty() {
+            self.end_game_with_crashes(crashes);
 
             return &self.state;
         }
 
-        self.process_active_powerup();
-        self.handle_eat_entity();
+        for index in 0..self.snakes.len() {
+            self.handle_eat_entity(index);
+        }
+
         self.generate_entities();
 
         &self.state
     }
 
+    /// Snakes running into each other. Meeting head on, or swapping places between ticks, takes out both snakes.
+    /// Running into the rest of another snake only takes out the one that ran into it.
+    fn snake_collisions(&self, previous_heads: &[Option<Coordinates>]) -> Vec<(SnakeId, Crash)> {
+        let mut crashes = vec![];
+
+        for (index, snake) in self.snakes.iter().enumerate() {
+            let Some(head) = snake.snake_get_head() else {
+                continue;
+            };
+
+            for (other_index, other) in self.snakes.iter().enumerate() {
+                if index == other_index {
+                    continue;
+                }
+
+                let swapped = previous_heads[other_index].as_ref() == Some(head)
+                    && other.snake_get_head() == previous_heads[index].as_ref();
+
+                if other.snake_get_head() == Some(head) || swapped {
+                    crashes.push((snake.get_id(), Crash::HeadOn));
+
+                    break;
+                }
+
+                if !snake.is_invincible() && other.snake_get_body().contains(head) {
+                    crashes.push((snake.get_id(), Crash::Into(other.get_id())));
+
+                    break;
+                }
+            }
+        }
+
+        crashes
+    }
+
+    /// End the game over the snakes that crashed this tick. In versus, the last snake left wins.
+    fn end_game_with_crashes(&mut self, crashes: Vec<(SnakeId, Crash)>) {
+        let outcome = match self.mode {
+            GameMode::Classic => None,
+            GameMode::Versus => {
+                let survivors: Vec<SnakeId> = self
+                    .snakes
+                    .iter()
+                    .map(SnakeBody::get_id)
+                    .filter(|id| crashes.iter().all(|(crashed, _)| crashed != id))
+                    .collect();
+
+                match survivors[..] {
+                    [winner] => Some(VersusOutcome::Winner(winner)),
+                    _ => Some(VersusOutcome::Draw),
+                }
+            }
+        };
+
+        let mut messages: Vec<String> = crashes
+            .iter()
+            .map(|(id, crash)| crash.describe(*id, self.mode))
+            .collect();
+
+        messages.dedup();
+
+        self.end_game(messages.join(" "), outcome);
+    }
+
     fn get_apples(&self) -> Vec<&EntityType> {
         self.entities
-            .iter()
-            .filter_map(|(_, entity)| match entity {
-                EntityType::Apple { .. } => Some(entity),
-                _ => None,
-            })
+            .values()
+            .filter(|entity| matches!(entity, EntityType::Apple { .. }))
             .collect()
     }
 
-    /// Identify if the snake is on a powerup and award it to the player on a match
-    fn handle_eat_entity(&mut self) {
-        match self.snake_on_entity() {
+    /// Identify if the snake is on a powerup and award it to the snake on a match. Obstacles are dealt with as crashes
+    /// before this, so a snake still on one is invincible and passes straight over it.
+    fn handle_eat_entity(&mut self, index: usize) {
+        let powerup = match self.snake_on_entity(index) {
             Some(EntityType::SupersnakePwrup { coordinates, .. }) => {
                 // Unwrapped because we know the snake has a head that is sitting on a powerup so it should always be Some
                 self.entities.remove(&coordinates.unwrap());
-                self.current_powerup = PowerupType::Supersnake { tick_duration: 100 };
+
+                PowerupType::Supersnake {
+                    tick_duration: self.config.supersnake_duration,
+                }
             }
             Some(EntityType::Apple { coordinates, .. }) => {
                 self.entities.remove(&coordinates.unwrap());
                 self.score += 1;
-            }
-            Some(EntityType::Obstacle { .. })
-                if !matches!(self.current_powerup, PowerupType::Supersnake { .. }) =>
-            {
-                self.state = GameState::GameOver {
-                    score: self.score,
-                    message: "You hit an obstacle! Press [R] to restart.".to_string(),
-                };
+                self.snakes[index].add_point();
+                self.events
+                    .push(GameEvent::AppleEaten(self.snakes[index].get_id()));
+
+                return;
             }
             Some(EntityType::SlowdownPwrup { coordinates, .. }) => {
                 self.entities.remove(&coordinates.unwrap());
-                self.current_powerup = PowerupType::Slowdown { tick_duration: 150 };
+
+                PowerupType::Slowdown {
+                    tick_duration: self.config.slowdown_duration,
+                }
+            }
+            Some(EntityType::MapWrapPwrup { coordinates, .. }) => {
+                self.entities.remove(&coordinates.unwrap());
+
+                PowerupType::MapWrap {
+                    tick_duration: self.config.map_wrap_duration,
+                }
+            }
+            Some(EntityType::MapGrowPwrup { coordinates, .. }) => {
+                self.entities.remove(&coordinates.unwrap());
+                self.grow_playfield();
+                self.events.push(GameEvent::MapGrown);
+
+                return;
             }
             _ => {
-                self.snake_remove_tail();
+                self.snakes[index].snake_remove_tail();
+
+                return;
             }
         };
+
+        self.snakes[index].set_powerup(powerup);
+        self.events.push(GameEvent::PowerupPickedUp(
+            self.snakes[index].get_id(),
+            powerup,
+        ));
     }
 
     /// Generate new powerups and apples under certain conditions
     fn generate_entities(&mut self) {
         let mut new_entities = vec![];
 
-        if self.score % 25 == 0 && self.score > 0 {
+        if self.score.is_multiple_of(self.config.supersnake_every) && self.score > 0 {
             self.add_entity(
                 |coords| {
                     new_entities.push(EntityType::new_supersnake(coords));
@@ -143,7 +376,7 @@ impl Game {
             self.score += 1;
         };
 
-        if self.score % 15 == 0 && self.score > 0 {
+        if self.score.is_multiple_of(self.config.slowdown_every) && self.score > 0 {
             self.add_entity(
                 |coords| {
                     new_entities.push(EntityType::new_slowdown(coords));
@@ -154,7 +387,32 @@ impl Game {
             self.score += 1;
         };
 
-        if self.get_apples().len() < 3 {
+        if self.score.is_multiple_of(self.config.map_wrap_every) && self.score > 0 {
+            self.add_entity(
+                |coords| {
+                    new_entities.push(EntityType::new_map_wrap(coords));
+                },
+                self.playfield_x,
+                self.playfield_y,
+            );
+            self.score += 1;
+        };
+
+        if self.score.is_multiple_of(self.config.map_grow_every)
+            && self.score > 0
+            && self.can_grow()
+        {
+            self.add_entity(
+                |coords| {
+                    new_entities.push(EntityType::new_map_grow(coords));
+                },
+                self.playfield_x,
+                self.playfield_y,
+            );
+            self.score += 1;
+        };
+
+        if self.get_apples().len() < self.config.apple_count {
             self.add_entity(
                 |coords| {
                     new_entities.push(EntityType::new_apple(coords));
@@ -164,7 +422,19 @@ impl Game {
             );
         };
 
-        let obstacle_coords = Vec::from([
+        for entity in new_entities {
+            let coordinates = &entity.get_coordinates().unwrap().clone();
+
+            self.entities.insert(*coordinates, entity);
+        }
+    }
+
+    /// Place the obstacles relative to the current size of the map, clearing any from a previous size
+    fn layout_obstacles(&mut self) {
+        self.entities
+            .retain(|_, entity| !matches!(entity, EntityType::Obstacle { .. }));
+
+        let obstacle_coords: [Coordinates; OBSTACLE_COUNT] = [
             // Top left
             Coordinates::new(5, 5),
             Coordinates::new(5, 6),
@@ -189,63 +459,175 @@ impl Game {
             Coordinates::new(self.playfield_x - 6, self.playfield_y - 8),
             Coordinates::new(self.playfield_x - 7, self.playfield_y - 6),
             Coordinates::new(self.playfield_x - 8, self.playfield_y - 6),
-        ]);
+        ];
 
-        for obstacle in obstacle_coords {
-            new_entities.push(EntityType::new_obstacle(obstacle));
-        }
+        // Leave out any that would land on a snake, right in front of one, or on an apple or powerup
+        let mut taken: HashSet<Coordinates> = self.entities.keys().copied().collect();
 
-        for entity in new_entities {
-            let coordinates = &entity.get_coordinates().unwrap().clone();
+        for snake in &self.snakes {
+            let body = snake.get_snake();
 
-            self.entities.insert(*coordinates, entity);
-        }
-    }
+            taken.extend(body);
 
-    // If there are any active powerups, process them and remove them if they have expired.
-    fn process_active_powerup(&mut self) {
-        match self.current_powerup {
-            PowerupType::Supersnake {
-                tick_duration: ref mut duration,
+            if let Some(ahead) = body.last().and_then(|head| {
+                head.step(
+                    *snake.snake_get_direction(),
+                    (self.playfield_x, self.playfield_y),
+                    true,
+                )
+            }) {
+                taken.insert(ahead);
             }
-            | PowerupType::Slowdown {
-                tick_duration: ref mut duration,
-            } => {
-                *duration -= 1;
+        }
 
-                if *duration == 0 {
-                    self.current_powerup = PowerupType::None;
-                }
+        for obstacle in obstacle_coords {
+            if !taken.contains(&obstacle) {
+                self.entities
+                    .insert(obstacle, EntityType::new_obstacle(obstacle));
             }
-
-            PowerupType::None => {}
         }
     }
 
+    fn can_grow(&self) -> bool {
+        self.playfield_x < self.config.max_width || self.playfield_y < self.config.max_height
+    }
+
+    /// Grow the map by a column and a row, up to the maximum size
+    fn grow_playfield(&mut self) {
+        self.playfield_x = (self.playfield_x + 1).min(self.config.max_width);
+        self.playfield_y = (self.playfield_y + 1).min(self.config.max_height);
+
+        self.layout_obstacles();
+    }
+
+    /// A slowed down snake slows the whole game down, since every snake moves on the same tick
     pub fn get_tick_speed(&self) -> u64 {
-        match self.current_powerup {
-            PowerupType::Slowdown { .. } => self.base_tick_speed + 50,
-            _ => self.base_tick_speed - self.score,
+        let slowed = self
+            .snakes
+            .iter()
+            .any(|snake| matches!(snake.get_powerup(), PowerupType::Slowdown { .. }));
+
+        match slowed {
+            true => self.config.tick_speed + self.config.slowdown_penalty,
+            false => self.config.tick_speed.saturating_sub(self.score).max(1),
         }
     }
 
     /// Get the entity that the snake is currently on
-    fn snake_on_entity(&self) -> Option<&EntityType> {
-        let snake_head_coords = self.snake_get_head().expect("Snake has no head!");
+    fn snake_on_entity(&self, index: usize) -> Option<&EntityType> {
+        let snake_head_coords = self.snakes[index]
+            .snake_get_head()
+            .expect("Snake has no head!");
 
         self.entities.get(snake_head_coords)
     }
 
-    pub fn get_current_powerup(&self) -> &PowerupType {
-        &self.current_powerup
+    pub fn get_snakes(&self) -> &[SnakeBody] {
+        &self.snakes
+    }
+
+    pub fn get_snake(&self, id: SnakeId) -> Option<&SnakeBody> {
+        self.snakes.iter().find(|snake| snake.get_id() == id)
+    }
+
+    /// Queue a turn for the snake. While it's slowed down, pressing the way it's already going skips ahead a tick.
+    pub fn turn(&mut self, id: SnakeId, direction: SnakeDirection) {
+        if self.is_playback() {
+            return;
+        }
+
+        // Skipping ahead moves every snake, which isn't one player's call to make in versus
+        let alone = self.snakes.len() == 1;
+        let Some(snake) = self.snakes.iter_mut().find(|snake| snake.get_id() == id) else {
+            return;
+        };
+
+        if alone
+            && direction == snake.get_heading()
+            && !snake.has_queued_turns()
+            && matches!(snake.get_powerup(), PowerupType::Slowdown { .. })
+        {
+            self.next();
+
+            return;
+        }
+
+        snake.snake_set_direction(direction);
     }
 
     pub fn get_score(&self) -> u64 {
         self.score
     }
 
+    /// The number of ticks processed while playing, including any fast-forwarded by the slowdown powerup
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn get_playfield_size(&self) -> (usize, usize) {
+        (self.playfield_x, self.playfield_y)
+    }
+
+    pub fn get_config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Every direction change made so far, enough to re-simulate this game with `Game::new_playback`
+    pub fn get_replay(&self) -> &Replay {
+        &self.recording
+    }
+
+    pub fn is_playback(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn set_keeps_seed(&mut self, keeps_seed: bool) {
+        self.keeps_seed = keeps_seed;
+    }
+
+    /// Restarts with a fresh seed, unless the seed was picked by the player or comes from a replay, in which case a
+    /// retry plays out the same spawns as the first attempt
     pub fn start_over(&mut self) {
-        *self = Self::new(self.playfield_x, self.playfield_y);
+        let seed = match self.keeps_seed || self.is_playback() {
+            true => self.seed,
+            false => rand::random(),
+        };
+        let mut game = Self::new(self.config.clone(), seed);
+
+        game.keeps_seed = self.keeps_seed;
+
+        game.set_mode(self.mode);
+        game.playback = self.playback.take();
+        game.suspended = self.suspended;
+
+        *self = game;
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            state: self.state.clone(),
+            tick: self.tick,
+            score: self.score,
+            tick_speed: self.get_tick_speed(),
+            playfield_size: self.get_playfield_size(),
+            snakes: self.snakes.clone(),
+            entities: self.entities.values().copied().collect(),
+            config: self.config.clone(),
+            seed: self.seed,
+            playback: self.is_playback(),
+            suspended: self.suspended,
+            mode: self.mode,
+        }
+    }
+
+    /// Hands over everything that happened since the last call. The slowdown powerup can run several ticks
+    /// between frames, so events pile up here until the game loop takes them.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
     }
 
     pub fn get_state(&self) -> &GameState {
@@ -253,60 +635,528 @@ impl Game {
     }
 
     pub fn play(&mut self) {
+        self.started_at = Some(Instant::now());
+        self.stopped_at = self.suspended.then(Instant::now);
         self.state = GameState::Playing;
     }
-}
 
-impl Snake for Game {
-    fn get_snake(&self) -> &Vec<Coordinates> {
-        &self.snake
+    /// Freeze the game mid-play. Queued directions are left alone until the game is resumed.
+    pub fn pause(&mut self) {
+        if let GameState::Playing = self.state {
+            let was_running = self.is_clock_running();
+
+            self.state = GameState::Paused {
+                selected: PauseMenuItem::Resume,
+                showing_settings: false,
+            };
+            self.update_clock(was_running);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let GameState::Paused { .. } = self.state {
+            let was_running = self.is_clock_running();
+
+            self.state = GameState::Playing;
+            self.update_clock(was_running);
+        }
     }
 
-    fn get_snake_mut(&mut self) -> &mut Vec<Coordinates> {
-        &mut self.snake
+    pub fn select_pause_menu_item(&mut self, item: PauseMenuItem) {
+        if let GameState::Paused { selected, .. } = &mut self.state {
+            *selected = item;
+        }
     }
 
-    fn snake_get_direction(&self) -> &SnakeDirection {
-        &self.current_direction
+    pub fn toggle_settings(&mut self) {
+        if let GameState::Paused {
+            showing_settings, ..
+        } = &mut self.state
+        {
+            *showing_settings = !*showing_settings;
+        }
     }
 
-    fn snake_set_direction(&mut self, direction: SnakeDirection) {
-        self.next_direction.push(direction);
+    pub fn show_leaderboard(&mut self) {
+        self.state = GameState::Leaderboard;
+    }
 
-        if matches!(self.current_powerup, PowerupType::Slowdown { .. }) {
-            while self.next_direction.len() > 1 && self.next_direction[0] == self.next_direction[1]
-            {
-                self.next();
+    pub fn show_intro(&mut self) {
+        self.state = GameState::Intro;
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Hold the game, for example while the terminal is too small to draw the map. A game being played is paused as
+    /// well, so it only carries on once the player resumes it.
+    pub fn set_suspended(&mut self, suspended: bool) {
+        if suspended {
+            self.pause();
+        }
+
+        let was_running = self.is_clock_running();
+
+        self.suspended = suspended;
+        self.update_clock(was_running);
+    }
+
+    /// Time only counts towards how long the game took while it's being played and can be seen
+    fn is_clock_running(&self) -> bool {
+        matches!(self.state, GameState::Playing) && !self.suspended
+    }
+
+    /// Stop or restart the clock after a pause or suspension, moving the start on by however long it was stopped
+    fn update_clock(&mut self, was_running: bool) {
+        match (was_running, self.is_clock_running()) {
+            (true, false) => self.stopped_at = Some(Instant::now()),
+            (false, true) => {
+                if let (Some(started_at), Some(stopped_at)) =
+                    (self.started_at, self.stopped_at.take())
+                {
+                    self.started_at = Some(started_at + stopped_at.elapsed());
+                }
             }
+            _ => (),
+        }
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    /// Choose the mode before the game starts, which puts the right number of snakes on the map.
+    /// A replay keeps the mode it was recorded in.
+    pub fn set_mode(&mut self, mode: GameMode) {
+        if !matches!(self.state, GameState::Intro) || self.is_playback() || mode == self.mode {
+            return;
+        }
+
+        self.mode = mode;
+        self.recording.mode = mode;
+        self.snakes = Self::spawn_snakes(mode, &self.config);
+    }
+
+    /// How long the game has been played for, in wall-clock time
+    pub fn get_duration(&self) -> Duration {
+        match (&self.state, self.started_at) {
+            (GameState::Playing | GameState::Paused { .. }, Some(started_at)) => self
+                .stopped_at
+                .unwrap_or_else(Instant::now)
+                .duration_since(started_at),
+            _ => self.duration,
+        }
+    }
+
+    /// Ask the player for their name on the game over screen
+    pub fn prompt_player_name(&mut self) {
+        if let GameState::GameOver { player_name, .. } = &mut self.state {
+            player_name.get_or_insert_with(String::new);
+        }
+    }
+
+    /// The name being typed on the game over screen, if the player is being asked for it
+    pub fn get_player_name_mut(&mut self) -> Option<&mut String> {
+        match &mut self.state {
+            GameState::GameOver { player_name, .. } => player_name.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Stop asking for the player's name and return what they typed
+    pub fn finish_player_name(&mut self) -> Option<String> {
+        match &mut self.state {
+            GameState::GameOver { player_name, .. } => player_name.take(),
+            _ => None,
         }
     }
 }
 
 impl Entity for Game {
     fn get_all_entities(&self) -> Vec<&EntityType> {
-        self.entities
-            .iter()
-            .filter_map(|(_, entity)| match entity {
-                EntityType::Apple { .. }
-                | EntityType::SupersnakePwrup { .. }
-                | EntityType::Obstacle { .. }
-                | EntityType::SlowdownPwrup { .. } => Some(entity),
-            })
-            .collect()
+        self.entities.values().collect()
     }
 
-    fn remove_entity(&mut self) {
-        let snake_head_coords = self.snake_get_head().unwrap().clone();
-
-        self.entities.remove(&snake_head_coords);
+    fn get_rng(&mut self) -> &mut StdRng {
+        &mut self.rng
     }
 
     fn get_entity_no_go_zones(&self) -> Vec<Coordinates> {
         let mut vec: Vec<Coordinates> = Vec::new();
 
-        vec.extend(&self.snake);
+        for snake in &self.snakes {
+            vec.extend(snake.get_snake());
+        }
+
         vec.extend(self.entities.keys());
 
         vec
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ControllerKind, Controllers};
+
+    /// Where the apples and powerups are after a few ticks of going straight
+    fn spawns(seed: u64) -> Vec<Coordinates> {
+        let mut game = Game::new(GameConfig::default(), seed);
+
+        game.play();
+
+        for _ in 0..5 {
+            game.next();
+        }
+
+        let mut spawns: Vec<Coordinates> = game.entities.keys().copied().collect();
+
+        spawns.sort_by_key(Coordinates::as_tuple);
+        spawns
+    }
+
+    #[test]
+    fn the_same_seed_spawns_the_same_entities() {
+        assert_eq!(spawns(42), spawns(42));
+        assert_ne!(spawns(42), spawns(43));
+    }
+
+    #[test]
+    fn restarting_only_keeps_a_seed_the_player_picked() {
+        let mut game = Game::new(GameConfig::default(), 42);
+
+        game.start_over();
+        assert_ne!(game.get_seed(), 42);
+
+        let mut game = Game::new(GameConfig::default(), 42);
+
+        game.set_keeps_seed(true);
+        game.start_over();
+        game.start_over();
+        assert_eq!(game.get_seed(), 42);
+    }
+
+    #[test]
+    fn pressing_the_way_a_slowed_snake_is_going_skips_a_tick() {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        game.play();
+        game.snakes[0].set_powerup(PowerupType::Slowdown { tick_duration: 10 });
+        game.turn(PLAYER_ONE, SnakeDirection::Right);
+        game.turn(PLAYER_ONE, SnakeDirection::Right);
+
+        assert_eq!(game.get_tick(), 2);
+
+        // A real turn is queued as normal instead
+        game.turn(PLAYER_ONE, SnakeDirection::Down);
+
+        assert_eq!(game.get_tick(), 2);
+
+        // Nor can one player hurry both snakes along in versus
+        let mut versus = Game::new(GameConfig::default(), 1);
+
+        versus.set_mode(GameMode::Versus);
+        versus.play();
+        versus.snakes[0].set_powerup(PowerupType::Slowdown { tick_duration: 10 });
+        versus.turn(PLAYER_ONE, SnakeDirection::Right);
+
+        assert_eq!(versus.get_tick(), 0);
+    }
+
+    fn snake(id: SnakeId, segments: &[(usize, usize)], direction: SnakeDirection) -> SnakeBody {
+        let segments = segments
+            .iter()
+            .map(|&(x, y)| Coordinates::new(x, y))
+            .collect();
+
+        SnakeBody::new(id, segments, direction, 2)
+    }
+
+    #[test]
+    fn turns_queued_before_a_pause_are_taken_in_order_after_it() {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        game.play();
+        game.turn(PLAYER_ONE, SnakeDirection::Down);
+        game.turn(PLAYER_ONE, SnakeDirection::Right);
+        game.pause();
+
+        for _ in 0..3 {
+            game.next();
+        }
+
+        assert_eq!(game.get_tick(), 0);
+
+        game.resume();
+        game.next();
+
+        assert_eq!(game.snakes[0].snake_get_direction(), &SnakeDirection::Down);
+
+        game.next();
+
+        assert_eq!(game.snakes[0].snake_get_direction(), &SnakeDirection::Right);
+        assert_eq!(
+            game.snakes[0].snake_get_head(),
+            Some(&Coordinates::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn skips_from_pressing_a_slowed_snakes_way_run_when_the_controller_steers() {
+        let mut game = Game::new(GameConfig::default(), 1);
+        let mut controllers = Controllers::default();
+
+        controllers.assign_kind(PLAYER_ONE, ControllerKind::Keyboard);
+        game.play();
+        game.snakes[0].set_powerup(PowerupType::Slowdown { tick_duration: 10 });
+
+        for _ in 0..3 {
+            controllers.press(&game, PLAYER_ONE, SnakeDirection::Right);
+        }
+
+        // Nothing moves as the keys come in, then the skips and the tick itself run one after another
+        assert_eq!(game.get_tick(), 0);
+
+        controllers.steer(&mut game);
+        game.next();
+
+        assert_eq!(game.get_tick(), 4);
+        assert_eq!(
+            game.snakes[0].snake_get_head(),
+            Some(&Coordinates::new(4, 0))
+        );
+    }
+
+    /// Play a single versus tick with the snakes placed by hand, returning how it ended
+    fn versus_tick(one: SnakeBody, two: SnakeBody) -> Option<(VersusOutcome, String)> {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        game.set_mode(GameMode::Versus);
+        game.snakes = vec![one, two];
+        game.play();
+        game.next();
+
+        match game.get_state() {
+            GameState::GameOver {
+                outcome: Some(outcome),
+                message,
+                ..
+            } => Some((*outcome, message.clone())),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn snakes_crash_into_each_other_by_the_versus_rules() {
+        use SnakeDirection::*;
+
+        let head_on = versus_tick(
+            snake(PLAYER_ONE, &[(3, 2)], Right),
+            snake(PLAYER_TWO, &[(5, 2)], Left),
+        );
+        assert_eq!(
+            head_on,
+            Some((
+                VersusOutcome::Draw,
+                "The snakes crashed head on!".to_string()
+            ))
+        );
+
+        // Heads passing through each other between ticks count as head on too
+        let swapped = versus_tick(
+            snake(PLAYER_ONE, &[(3, 2)], Right),
+            snake(PLAYER_TWO, &[(4, 2)], Left),
+        );
+        assert_eq!(
+            swapped.map(|(outcome, _)| outcome),
+            Some(VersusOutcome::Draw)
+        );
+
+        let into_body = versus_tick(
+            snake(PLAYER_ONE, &[(3, 2)], Down),
+            snake(PLAYER_TWO, &[(3, 3), (4, 3)], Right),
+        );
+        assert_eq!(
+            into_body,
+            Some((
+                VersusOutcome::Winner(PLAYER_TWO),
+                "Player one ran into player two!".to_string()
+            ))
+        );
+
+        let apart = versus_tick(
+            snake(PLAYER_ONE, &[(3, 2)], Right),
+            snake(PLAYER_TWO, &[(3, 4)], Left),
+        );
+        assert_eq!(apart, None);
+    }
+
+    /// Play a single tick with one snake placed by hand on a map with nothing but the given obstacles
+    fn classic_tick(
+        mut one: SnakeBody,
+        powerup: PowerupType,
+        obstacles: &[(usize, usize)],
+    ) -> Game {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        one.set_powerup(powerup);
+        game.snakes = vec![one];
+        game.entities = obstacles
+            .iter()
+            .map(|&(x, y)| {
+                let coordinates = Coordinates::new(x, y);
+
+                (coordinates, EntityType::new_obstacle(coordinates))
+            })
+            .collect();
+        game.play();
+        game.next();
+        game
+    }
+
+    fn game_over_message(game: &Game) -> Option<&str> {
+        match game.get_state() {
+            GameState::GameOver { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn map_wrap_takes_the_snake_out_of_the_opposite_wall() {
+        use SnakeDirection::*;
+
+        let wrap = PowerupType::MapWrap { tick_duration: 10 };
+        let sides = [
+            ((0, 3), Left, (19, 3)),
+            ((19, 3), Right, (0, 3)),
+            ((3, 0), Up, (3, 19)),
+            ((3, 19), Down, (3, 0)),
+        ];
+
+        for (from, direction, to) in sides {
+            let game = classic_tick(snake(PLAYER_ONE, &[from], direction), wrap, &[]);
+
+            assert_eq!(game_over_message(&game), None);
+            assert_eq!(
+                game.snakes[0].snake_get_head(),
+                Some(&Coordinates::new(to.0, to.1))
+            );
+        }
+
+        // Without the powerup the wall is as hard as ever
+        let game = classic_tick(snake(PLAYER_ONE, &[(0, 3)], Left), PowerupType::None, &[]);
+
+        assert_eq!(game_over_message(&game), Some("Snake hit a wall!"));
+    }
+
+    #[test]
+    fn the_wall_is_back_once_map_wrap_runs_out() {
+        let mut game = classic_tick(
+            snake(PLAYER_ONE, &[(18, 3)], SnakeDirection::Right),
+            PowerupType::MapWrap { tick_duration: 1 },
+            &[],
+        );
+
+        assert!(matches!(game.snakes[0].get_powerup(), PowerupType::None));
+
+        game.next();
+
+        assert_eq!(game_over_message(&game), Some("Snake hit a wall!"));
+    }
+
+    #[test]
+    fn a_wrapped_snake_still_crashes_into_things() {
+        let wrap = PowerupType::MapWrap { tick_duration: 10 };
+
+        let game = classic_tick(
+            snake(PLAYER_ONE, &[(19, 3)], SnakeDirection::Right),
+            wrap,
+            &[(0, 3)],
+        );
+
+        assert_eq!(game_over_message(&game), Some("You hit an obstacle!"));
+
+        // Curled round so the cell it wraps into is its own body
+        let game = classic_tick(
+            snake(
+                PLAYER_ONE,
+                &[(1, 5), (0, 5), (0, 6), (19, 6), (19, 5)],
+                SnakeDirection::Right,
+            ),
+            wrap,
+            &[],
+        );
+
+        assert_eq!(game_over_message(&game), Some("You ate yourself!"));
+    }
+
+    #[test]
+    fn nothing_spawns_once_the_map_is_full() {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        for x in 0..20 {
+            for y in 0..20 {
+                let coordinates = Coordinates::new(x, y);
+
+                game.entities
+                    .entry(coordinates)
+                    .or_insert_with(|| EntityType::new_obstacle(coordinates));
+            }
+        }
+
+        game.generate_entities();
+
+        assert_eq!(game.entities.len(), 400);
+    }
+
+    #[test]
+    fn a_growing_map_keeps_its_obstacles_off_snakes_and_entities() {
+        let mut game = Game::new(GameConfig::default(), 1);
+        let apple = Coordinates::new(6, 5);
+
+        game.snakes = vec![snake(
+            PLAYER_ONE,
+            &[(4, 8), (5, 8), (5, 7)],
+            SnakeDirection::Up,
+        )];
+        game.entities.insert(apple, EntityType::new_apple(apple));
+        game.grow_playfield();
+
+        // The snake's body, the cell ahead of its head and the apple stay clear, and the rest go down as usual
+        assert_eq!(game.entities.get(&Coordinates::new(5, 7)), None);
+        assert_eq!(game.entities.get(&Coordinates::new(5, 6)), None);
+        assert_eq!(
+            game.entities.get(&apple),
+            Some(&EntityType::new_apple(apple))
+        );
+        assert_eq!(
+            game.entities.get(&Coordinates::new(5, 5)),
+            Some(&EntityType::new_obstacle(Coordinates::new(5, 5)))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn time_held_for_a_small_terminal_doesnt_count_towards_the_duration() {
+        let mut game = Game::new(GameConfig::default(), 1);
+
+        game.play();
+        tokio::time::advance(Duration::from_secs(1)).await;
+        game.set_suspended(true);
+        tokio::time::advance(Duration::from_secs(5)).await;
+
+        assert_eq!(game.get_duration(), Duration::from_secs(1));
+
+        // Growing the terminal back leaves the game paused until the player resumes it
+        game.set_suspended(false);
+        tokio::time::advance(Duration::from_secs(5)).await;
+
+        assert!(matches!(game.get_state(), GameState::Paused { .. }));
+        assert_eq!(game.get_duration(), Duration::from_secs(1));
+
+        game.resume();
+        tokio::time::advance(Duration::from_secs(2)).await;
+
+        assert_eq!(game.get_duration(), Duration::from_secs(3));
+    }
+}
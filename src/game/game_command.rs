@@ -0,0 +1,25 @@
+use super::{
+    game_mode::GameMode, pause_menu::PauseMenuItem, snake_body::SnakeId, traits::SnakeDirection,
+};
+
+/// Everything the player can ask the game to do. Commands are sent to the game loop, which owns the game.
+pub enum GameCommand {
+    Turn(SnakeId, SnakeDirection),
+    SetMode(GameMode), // Only from the intro, before the game starts
+    Start,
+    Pause,
+    Resume,
+    Restart, // Back to the intro with a fresh game
+    Quit,
+    ShowLeaderboard,
+    ShowIntro,
+    SelectPauseMenuItem(PauseMenuItem),
+    ToggleSettings,
+    TypeName(char),
+    EraseName,
+    SubmitName,
+    SkipName,
+    Resize(u16, u16), // The new terminal size in columns and rows
+    ToggleDebugHud,
+    CyclePalette, // Only changes how the game is drawn, so the game loop hands it to the frontends
+}
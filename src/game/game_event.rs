@@ -0,0 +1,22 @@
+use super::{powerup::PowerupType, snake_body::SnakeId};
+
+/// Something that happened while the game ticked, for frontends that announce what happens instead of drawing it
+#[derive(Clone, Copy)]
+pub enum GameEvent {
+    AppleEaten(SnakeId),
+    PowerupPickedUp(SnakeId, PowerupType),
+    PowerupRanOut(SnakeId),
+    MapGrown,
+}
+
+impl GameEvent {
+    /// The snake it happened to, if it happened to one
+    pub fn get_snake(&self) -> Option<SnakeId> {
+        match self {
+            GameEvent::AppleEaten(id)
+            | GameEvent::PowerupPickedUp(id, _)
+            | GameEvent::PowerupRanOut(id) => Some(*id),
+            GameEvent::MapGrown => None,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Versus, // Two players on one keyboard, sharing the map
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameMode::Classic => write!(f, "Classic"),
+            GameMode::Versus => write!(f, "Versus"),
+        }
+    }
+}
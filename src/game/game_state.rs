@@ -1,5 +1,25 @@
+use super::{pause_menu::PauseMenuItem, snake_body::SnakeId};
+
+/// How a versus game ended
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VersusOutcome {
+    Winner(SnakeId),
+    Draw, // Every snake crashed on the same tick
+}
+
+#[derive(Clone)]
 pub enum GameState {
     Intro,
+    Leaderboard,
     Playing,
-    GameOver { score: u64, message: String },
+    Paused {
+        selected: PauseMenuItem,
+        showing_settings: bool,
+    },
+    GameOver {
+        score: u64,
+        message: String,
+        player_name: Option<String>, // Some while the player is typing their name for the leaderboard
+        outcome: Option<VersusOutcome>, // Only set for versus games
+    },
 }
@@ -0,0 +1,67 @@
+use std::{env, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Which set of characters the game is drawn with. Not every terminal can show emoji, or show them at a steady width.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphTheme {
+    Emoji,
+    Unicode, // Block and box drawing characters, for terminals that can do Unicode but not emoji
+    Ascii,
+}
+
+impl GlyphTheme {
+    /// Guess the best theme the terminal can show. Emoji need a UTF-8 locale and a terminal that isn't the Linux
+    /// console, and tmux and screen are known to get their width wrong.
+    pub fn detect() -> Self {
+        if cfg!(windows) {
+            return Self::Emoji;
+        }
+
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if !(locale.contains("utf-8") || locale.contains("utf8"))
+            || term == "linux"
+            || term == "dumb"
+        {
+            Self::Ascii
+        } else if env::var_os("TMUX").is_some()
+            || term.starts_with("screen")
+            || term.starts_with("tmux")
+        {
+            Self::Unicode
+        } else {
+            Self::Emoji
+        }
+    }
+}
+
+impl Display for GlyphTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlyphTheme::Emoji => write!(f, "emoji"),
+            GlyphTheme::Unicode => write!(f, "unicode"),
+            GlyphTheme::Ascii => write!(f, "ascii"),
+        }
+    }
+}
+
+impl FromStr for GlyphTheme {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "emoji" => Ok(Self::Emoji),
+            "unicode" => Ok(Self::Unicode),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(()),
+        }
+    }
+}
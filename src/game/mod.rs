@@ -1,13 +1,36 @@
+mod autopilot;
 mod characters;
 mod entity_type;
+#[allow(clippy::module_inception)]
 mod game;
+mod game_command;
+mod game_event;
+mod game_mode;
 mod game_state;
+mod glyph_theme;
+mod palette;
+mod pause_menu;
 mod powerup;
+mod replay;
+mod simulation;
+mod snake_body;
+mod snapshot;
 mod traits;
 
+pub use autopilot::*;
 pub use characters::*;
 pub use entity_type::*;
 pub use game::*;
+pub use game_command::*;
+pub use game_event::*;
+pub use game_mode::*;
 pub use game_state::*;
+pub use glyph_theme::*;
+pub use palette::*;
+pub use pause_menu::*;
 pub use powerup::*;
+pub use replay::*;
+pub use simulation::*;
+pub use snake_body::*;
+pub use snapshot::*;
 pub use traits::*;
@@ -0,0 +1,132 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{Rgb, Style};
+
+use super::characters::Characters;
+
+/// The set of colours the game is drawn in. The accessible palettes also give each snake body its own shape,
+/// so the active powerup never has to be told apart by colour alone.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    #[default]
+    Standard,
+    ColorBlind, // Okabe and Ito's palette, which stays distinct with every common kind of colour blindness
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [
+        Palette::Standard,
+        Palette::ColorBlind,
+        Palette::HighContrast,
+    ];
+
+    /// The palette after this one, wrapping around to the first
+    pub fn next(&self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|palette| palette == self)
+            .unwrap();
+
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Returns true if glyphs should differ in shape as well as colour
+    pub fn distinct_shapes(&self) -> bool {
+        *self != Palette::Standard
+    }
+
+    pub fn style(&self, character: Characters) -> Style {
+        let foreground = |r, g, b| Style {
+            foreground: Some(Rgb(r, g, b)),
+            background: None,
+        };
+
+        match self {
+            Palette::Standard => match character {
+                Characters::SnakeHead => foreground(240, 240, 240),
+                Characters::SnakeBody => foreground(60, 200, 60),
+                Characters::SnakeBodySuper => foreground(60, 120, 240),
+                Characters::SnakeBodySlow => foreground(230, 200, 40),
+                Characters::SnakeBodyWrap => foreground(170, 80, 220),
+                Characters::Obstacle => foreground(220, 50, 50),
+                Characters::Grass => foreground(40, 110, 40),
+                Characters::Apple => foreground(110, 210, 60),
+                Characters::SupersnakePwrup => foreground(60, 120, 240),
+                Characters::SlowdownPwrup => foreground(230, 200, 40),
+                Characters::MapWrapPwrup => foreground(170, 80, 220),
+                Characters::MapGrowPwrup => foreground(60, 200, 120),
+                Characters::RivalHead => foreground(250, 170, 60),
+                Characters::RivalBody => foreground(240, 130, 30),
+            },
+            Palette::ColorBlind => match character {
+                Characters::SnakeHead => foreground(240, 240, 240),
+                Characters::SnakeBody => foreground(0, 158, 115),
+                Characters::SnakeBodySuper => foreground(0, 114, 178),
+                Characters::SnakeBodySlow => foreground(240, 228, 66),
+                Characters::SnakeBodyWrap => foreground(204, 121, 167),
+                Characters::Obstacle => foreground(213, 94, 0),
+                Characters::Grass => foreground(90, 90, 90),
+                Characters::Apple => foreground(230, 159, 0),
+                Characters::SupersnakePwrup => foreground(0, 114, 178),
+                Characters::SlowdownPwrup => foreground(240, 228, 66),
+                Characters::MapWrapPwrup => foreground(204, 121, 167),
+                Characters::MapGrowPwrup => foreground(86, 180, 233),
+                Characters::RivalHead => foreground(86, 180, 233),
+                Characters::RivalBody => foreground(86, 180, 233),
+            },
+            Palette::HighContrast => {
+                let on_black = |r, g, b| Style {
+                    foreground: Some(Rgb(r, g, b)),
+                    background: Some(Rgb(0, 0, 0)),
+                };
+
+                match character {
+                    Characters::SnakeHead => on_black(255, 255, 255),
+                    Characters::SnakeBody => on_black(255, 255, 255),
+                    Characters::SnakeBodySuper => on_black(0, 255, 255),
+                    Characters::SnakeBodySlow => on_black(255, 255, 0),
+                    Characters::SnakeBodyWrap => on_black(255, 0, 255),
+                    Characters::Obstacle => Style {
+                        foreground: Some(Rgb(0, 0, 0)),
+                        background: Some(Rgb(255, 255, 255)),
+                    },
+                    Characters::Grass => on_black(128, 128, 128),
+                    Characters::Apple => on_black(0, 255, 0),
+                    Characters::SupersnakePwrup => on_black(0, 255, 255),
+                    Characters::SlowdownPwrup => on_black(255, 255, 0),
+                    Characters::MapWrapPwrup => on_black(255, 0, 255),
+                    Characters::MapGrowPwrup => on_black(0, 255, 0),
+                    Characters::RivalHead => on_black(255, 160, 0),
+                    Characters::RivalBody => on_black(255, 160, 0),
+                }
+            }
+        }
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Palette::Standard => write!(f, "Standard"),
+            Palette::ColorBlind => write!(f, "Colour-blind safe"),
+            Palette::HighContrast => write!(f, "High contrast"),
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "standard" => Ok(Self::Standard),
+            "color_blind" => Ok(Self::ColorBlind),
+            "high_contrast" => Ok(Self::HighContrast),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum PauseMenuItem {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl PauseMenuItem {
+    pub const ALL: [PauseMenuItem; 4] = [
+        PauseMenuItem::Resume,
+        PauseMenuItem::Restart,
+        PauseMenuItem::Settings,
+        PauseMenuItem::Quit,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseMenuItem::Resume => "Resume",
+            PauseMenuItem::Restart => "Restart",
+            PauseMenuItem::Settings => "Settings",
+            PauseMenuItem::Quit => "Quit",
+        }
+    }
+
+    /// The item below this one, wrapping around to the top
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|item| item == self).unwrap();
+
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The item above this one, wrapping around to the bottom
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|item| item == self).unwrap();
+
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
@@ -1,6 +1,7 @@
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum PowerupType {
     Supersnake { tick_duration: u64 },
     Slowdown { tick_duration: u64 },
+    MapWrap { tick_duration: u64 },
     None,
 }
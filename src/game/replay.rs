@@ -0,0 +1,159 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::{ConfigError, GameConfig};
+
+use super::{game_mode::GameMode, snake_body::SnakeId, traits::SnakeDirection};
+
+/// Bump whenever the replay format or the game rules change in a way that would make old replays play out differently
+pub const REPLAY_VERSION: u32 = 3;
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("Could not access the replay file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read the replay file: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Could not write the replay file: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Replay version {0} is not supported, expected version {REPLAY_VERSION}.")]
+    UnsupportedVersion(u32),
+    #[error("The replay was recorded with an invalid config: {0}")]
+    InvalidConfig(#[from] ConfigError),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct ReplayInput {
+    pub tick: u64,
+    #[serde(default)]
+    pub snake: SnakeId, // Left out by replays from before versus games, which only had player one
+    pub direction: SnakeDirection,
+}
+
+/// Everything needed to re-simulate a game: the seed, the config it was played with and every direction change with the tick it applied on
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub config: GameConfig,
+    #[serde(default)]
+    pub mode: GameMode,
+    pub inputs: Vec<ReplayInput>,
+}
+
+impl Replay {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            mode: GameMode::Classic,
+            inputs: vec![],
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReplayError> {
+        let replay: Self = toml::from_str(&fs::read_to_string(path)?)?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(replay.version));
+        }
+
+        // A hand edited replay could hold a config the game can't be set up with
+        replay.config.validate()?;
+
+        Ok(replay)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ReplayError> {
+        fs::write(path, toml::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, tick: u64, snake: SnakeId, direction: SnakeDirection) {
+        self.inputs.push(ReplayInput {
+            tick,
+            snake,
+            direction,
+        });
+    }
+
+    /// The direction the snake changed to on the given tick, if it changed at all
+    pub fn direction_at(&self, tick: u64, snake: SnakeId) -> Option<SnakeDirection> {
+        self.inputs
+            .iter()
+            .find(|input| input.tick == tick && input.snake == snake)
+            .map(|input| input.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, GameState, Simulation, Snake, PLAYER_ONE};
+
+    #[test]
+    fn a_saved_replay_plays_out_the_same_game() {
+        let mut simulation = Simulation::new(GameConfig::default(), 5);
+
+        simulation.step(&[SnakeDirection::Down]);
+        simulation.advance(3);
+        simulation.step(&[SnakeDirection::Right]);
+        simulation.advance(3);
+        simulation.step(&[SnakeDirection::Down]);
+        simulation.advance(100);
+
+        let played = simulation.into_game();
+        let path = std::env::temp_dir().join(format!("snake-replay-{}.toml", std::process::id()));
+
+        played.get_replay().save(&path).unwrap();
+
+        let replay = Replay::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        let mut watched = Game::new_playback(replay.unwrap());
+
+        watched.play();
+
+        while !matches!(watched.get_state(), GameState::GameOver { .. }) {
+            assert!(watched.get_tick() <= played.get_tick());
+
+            watched.next();
+        }
+
+        assert!(matches!(played.get_state(), GameState::GameOver { .. }));
+        assert_eq!(watched.get_tick(), played.get_tick());
+        assert_eq!(watched.get_score(), played.get_score());
+        assert_eq!(
+            watched
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone()),
+            played
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone())
+        );
+    }
+
+    #[test]
+    fn a_replay_with_an_invalid_config_is_rejected() {
+        let config = GameConfig {
+            width: 3,
+            ..GameConfig::default()
+        };
+
+        let path =
+            std::env::temp_dir().join(format!("snake-bad-replay-{}.toml", std::process::id()));
+
+        Replay::new(config, 1).save(&path).unwrap();
+
+        let replay = Replay::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(replay, Err(ReplayError::InvalidConfig(_))));
+    }
+}
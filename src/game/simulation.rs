@@ -0,0 +1,112 @@
+use crate::{config::GameConfig, controller::Controllers};
+
+use super::{game::Game, game_state::GameState, snake_body::PLAYER_ONE, traits::SnakeDirection};
+
+/// Drives a `Game` without a terminal, tokio tasks or sleeps, so ticks run as fast as the CPU allows.
+///
+/// The game is started straight away, skipping the intro screen.
+pub struct Simulation {
+    game: Game,
+}
+
+impl Simulation {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let mut game = Game::new(config, seed);
+
+        game.play();
+
+        Self { game }
+    }
+
+    /// Queue up the given directions, as if they were pressed in order, then advance a single tick
+    pub fn step(&mut self, inputs: &[SnakeDirection]) -> &GameState {
+        for direction in inputs {
+            self.game.turn(PLAYER_ONE, *direction);
+        }
+
+        self.game.next()
+    }
+
+    /// Let the controllers steer, the way the game loop does before each tick, then advance a single tick
+    pub fn step_with(&mut self, controllers: &mut Controllers) -> &GameState {
+        controllers.steer(&mut self.game);
+
+        self.game.next()
+    }
+
+    /// Advance up to the given amount of ticks without any input, stopping early if the game ends
+    pub fn advance(&mut self, ticks: u64) -> &GameState {
+        for _ in 0..ticks {
+            if self.is_over() {
+                break;
+            }
+
+            self.game.next();
+        }
+
+        self.game.get_state()
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.game.get_state(), GameState::GameOver { .. })
+    }
+
+    /// Read-only access to the game for inspecting the snake, entities, score and state
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coordinates::Coordinates,
+        game::{Entity, EntityType, Snake},
+    };
+
+    /// Play the same handful of turns on the given seed
+    fn play(seed: u64) -> Game {
+        let mut simulation = Simulation::new(GameConfig::default(), seed);
+
+        simulation.step(&[SnakeDirection::Down]);
+        simulation.advance(2);
+        simulation.step(&[SnakeDirection::Right]);
+        simulation.advance(10);
+
+        simulation.into_game()
+    }
+
+    fn entities(game: &Game) -> Vec<EntityType> {
+        let mut entities: Vec<EntityType> = game.get_all_entities().into_iter().copied().collect();
+
+        entities.sort_by_key(|entity| entity.get_coordinates().map(Coordinates::as_tuple));
+
+        entities
+    }
+
+    #[test]
+    fn the_same_seed_and_inputs_play_the_same_game() {
+        let first = play(42);
+        let second = play(42);
+
+        assert_eq!(entities(&first), entities(&second));
+        assert_eq!(
+            first
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone()),
+            second
+                .get_snake(PLAYER_ONE)
+                .map(|snake| snake.get_snake().clone())
+        );
+        assert_eq!(first.get_score(), second.get_score());
+        assert_eq!(first.get_tick(), second.get_tick());
+
+        // Obstacles always sit in the same place, so only the spawns can tell the seeds apart
+        assert_ne!(entities(&first), entities(&play(43)));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::coordinates::Coordinates;
+
+use super::{
+    powerup::PowerupType,
+    traits::{Snake, SnakeDirection},
+};
+
+/// Tells the snakes in a game apart, and which player each one belongs to
+pub type SnakeId = usize;
+
+/// The snake steered by the first, or only, player
+pub const PLAYER_ONE: SnakeId = 0;
+
+/// The snake steered by the second player in a versus game
+pub const PLAYER_TWO: SnakeId = 1;
+
+/// How a player is named on screen
+pub fn player_label(id: SnakeId) -> &'static str {
+    match id {
+        PLAYER_ONE => "Player one",
+        _ => "Player two",
+    }
+}
+
+/// One snake on the map, with everything that belongs to it rather than to the game: where it is, where it's going,
+/// what powerup it has and how many apples it has eaten.
+#[derive(Clone)]
+pub struct SnakeBody {
+    id: SnakeId,
+    segments: Vec<Coordinates>,          // From the tail to the head
+    current_direction: SnakeDirection,   // Only updates next game tick
+    next_direction: Vec<SnakeDirection>, // Turns waiting for the coming ticks, one is taken each tick
+    max_queued_turns: usize,
+    powerup: PowerupType,
+    score: u64, // Apples eaten by this snake, so each player in a versus game has their own
+}
+
+impl SnakeBody {
+    pub fn new(
+        id: SnakeId,
+        segments: Vec<Coordinates>,
+        direction: SnakeDirection,
+        max_queued_turns: usize,
+    ) -> Self {
+        Self {
+            id,
+            segments,
+            current_direction: direction,
+            next_direction: Vec::new(),
+            max_queued_turns,
+            powerup: PowerupType::None,
+            score: 0,
+        }
+    }
+
+    pub fn get_id(&self) -> SnakeId {
+        self.id
+    }
+
+    pub fn get_score(&self) -> u64 {
+        self.score
+    }
+
+    pub fn add_point(&mut self) {
+        self.score += 1;
+    }
+
+    pub fn get_powerup(&self) -> &PowerupType {
+        &self.powerup
+    }
+
+    pub fn set_powerup(&mut self, powerup: PowerupType) {
+        self.powerup = powerup;
+    }
+
+    /// Count down the active powerup. Returns true on the tick it runs out.
+    pub fn wear_off_powerup(&mut self) -> bool {
+        match &mut self.powerup {
+            PowerupType::Supersnake {
+                tick_duration: duration,
+            }
+            | PowerupType::Slowdown {
+                tick_duration: duration,
+            }
+            | PowerupType::MapWrap {
+                tick_duration: duration,
+            } => {
+                *duration -= 1;
+
+                if *duration == 0 {
+                    self.powerup = PowerupType::None;
+
+                    return true;
+                }
+
+                false
+            }
+            PowerupType::None => false,
+        }
+    }
+
+    /// Obstacles and snake bodies, its own or another's, can't hurt it while the supersnake powerup lasts
+    pub fn is_invincible(&self) -> bool {
+        matches!(self.powerup, PowerupType::Supersnake { .. })
+    }
+
+    /// The way the snake will be going once every queued turn has been taken
+    pub fn get_heading(&self) -> SnakeDirection {
+        *self
+            .next_direction
+            .last()
+            .unwrap_or(&self.current_direction)
+    }
+
+    pub fn has_queued_turns(&self) -> bool {
+        !self.next_direction.is_empty()
+    }
+
+    /// Take the next queued turn, if there is one. Returns the new direction if the snake turned.
+    pub fn take_queued_turn(&mut self) -> Option<SnakeDirection> {
+        if self.next_direction.is_empty() {
+            return None;
+        }
+
+        let direction = self.next_direction.remove(0);
+
+        self.turn_now(direction)
+    }
+
+    /// Turn straight away, skipping the queue. Returns the new direction if the snake turned.
+    pub fn turn_now(&mut self, direction: SnakeDirection) -> Option<SnakeDirection> {
+        let turned = direction != self.current_direction;
+
+        self.current_direction = direction;
+
+        turned.then_some(direction)
+    }
+}
+
+impl Snake for SnakeBody {
+    fn get_snake(&self) -> &Vec<Coordinates> {
+        &self.segments
+    }
+
+    fn get_snake_mut(&mut self) -> &mut Vec<Coordinates> {
+        &mut self.segments
+    }
+
+    fn snake_get_direction(&self) -> &SnakeDirection {
+        &self.current_direction
+    }
+
+    fn snake_can_wrap(&self) -> bool {
+        matches!(self.powerup, PowerupType::MapWrap { .. })
+    }
+
+    /// Queue a turn for a coming tick. Turns are checked against the last queued one, since that's the way the snake
+    /// will be going by then, so turning back on itself, repeats and anything past the queue's limit are dropped.
+    fn snake_set_direction(&mut self, direction: SnakeDirection) {
+        let heading = self.get_heading();
+
+        if direction == heading
+            || direction == heading.opposite()
+            || self.next_direction.len() >= self.max_queued_turns
+        {
+            return;
+        }
+
+        self.next_direction.push(direction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A snake three cells long heading right
+    fn long_snake() -> SnakeBody {
+        SnakeBody::new(
+            PLAYER_ONE,
+            vec![
+                Coordinates::new(5, 10),
+                Coordinates::new(6, 10),
+                Coordinates::new(7, 10),
+            ],
+            SnakeDirection::Right,
+            2,
+        )
+    }
+
+    /// Move the snake a cell without growing, like a tick where nothing is eaten
+    fn step(snake: &mut SnakeBody) {
+        snake.take_queued_turn();
+        snake.snake_add_head(20, 20).unwrap();
+        snake.snake_remove_tail();
+    }
+
+    #[test]
+    fn turning_back_on_the_snake_is_ignored() {
+        let mut snake = long_snake();
+
+        snake.snake_set_direction(SnakeDirection::Left);
+        step(&mut snake);
+
+        assert!(!snake.snake_eating_itself());
+        assert_eq!(snake.snake_get_head(), Some(&Coordinates::new(8, 10)));
+    }
+
+    #[test]
+    fn rapid_turns_within_a_tick_are_checked_against_the_last_queued_turn() {
+        let mut snake = long_snake();
+
+        // Down would be fine going right, but not straight after turning up
+        for direction in [
+            SnakeDirection::Up,
+            SnakeDirection::Up,
+            SnakeDirection::Down,
+            SnakeDirection::Left,
+        ] {
+            snake.snake_set_direction(direction);
+        }
+
+        assert_eq!(
+            snake.next_direction,
+            [SnakeDirection::Up, SnakeDirection::Left]
+        );
+
+        step(&mut snake);
+        step(&mut snake);
+
+        assert!(!snake.snake_eating_itself());
+        assert_eq!(snake.snake_get_head(), Some(&Coordinates::new(6, 9)));
+    }
+
+    #[test]
+    fn turns_past_the_queue_limit_are_dropped() {
+        let mut snake = long_snake();
+
+        for direction in [
+            SnakeDirection::Up,
+            SnakeDirection::Left,
+            SnakeDirection::Down,
+            SnakeDirection::Right,
+            SnakeDirection::Up,
+        ] {
+            snake.snake_set_direction(direction);
+        }
+
+        assert_eq!(snake.next_direction.len(), snake.max_queued_turns);
+
+        step(&mut snake);
+        step(&mut snake);
+        step(&mut snake);
+
+        // Once the queue has emptied the snake carries on the way the last kept turn left it
+        assert_eq!(snake.snake_get_head(), Some(&Coordinates::new(5, 9)));
+        assert!(!snake.has_queued_turns());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::config::GameConfig;
+
+use super::{
+    entity_type::EntityType,
+    game_mode::GameMode,
+    game_state::GameState,
+    snake_body::{SnakeBody, SnakeId},
+};
+
+/// A copy of everything needed to show the game at one moment, so it can be drawn without holding on to the game
+#[derive(Clone)]
+pub struct GameSnapshot {
+    pub state: GameState,
+    pub tick: u64,
+    pub score: u64,
+    pub tick_speed: u64,
+    pub playfield_size: (usize, usize),
+    pub snakes: Vec<SnakeBody>,
+    pub entities: Vec<EntityType>,
+    pub config: GameConfig,
+    pub seed: u64,
+    pub playback: bool,
+    pub suspended: bool,
+    pub mode: GameMode,
+}
+
+impl GameSnapshot {
+    pub fn snake(&self, id: SnakeId) -> Option<&SnakeBody> {
+        self.snakes.iter().find(|snake| snake.get_id() == id)
+    }
+}
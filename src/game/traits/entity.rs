@@ -1,3 +1,7 @@
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+
 use crate::{coordinates::Coordinates, game::entity_type::EntityType};
 
 pub trait Entity {
@@ -6,16 +10,24 @@ pub trait Entity {
     /// Returns a vector of coordinates that entities cannot spawn on, for example, the snake
     fn get_entity_no_go_zones(&self) -> Vec<Coordinates>;
 
-    fn remove_entity(&mut self);
+    /// The random number generator used to place new entities, owned by the implementor so spawns can be reproduced
+    fn get_rng(&mut self) -> &mut StdRng;
 
+    /// Place an entity on a random free cell. Nothing is placed if every cell is taken.
     fn add_entity<F>(&mut self, new_coords: F, max_x: usize, max_y: usize)
     where
         F: FnOnce(Coordinates),
     {
+        let no_go_zones: HashSet<Coordinates> = self.get_entity_no_go_zones().into_iter().collect();
+
+        if no_go_zones.len() >= max_x * max_y {
+            return;
+        }
+
         loop {
-            let new_entity_location = Coordinates::new_random(max_x, max_y);
+            let new_entity_location = Coordinates::new_random(max_x, max_y, self.get_rng());
 
-            if !new_entity_location.intersects_multiple(&self.get_entity_no_go_zones()) {
+            if !no_go_zones.contains(&new_entity_location) {
                 new_coords(new_entity_location);
 
                 break;
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::coordinates::Coordinates;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum SnakeDirection {
     Up,
     Down,
@@ -10,6 +11,17 @@ pub enum SnakeDirection {
     Right,
 }
 
+impl SnakeDirection {
+    pub fn opposite(&self) -> Self {
+        match self {
+            SnakeDirection::Up => SnakeDirection::Down,
+            SnakeDirection::Down => SnakeDirection::Up,
+            SnakeDirection::Left => SnakeDirection::Right,
+            SnakeDirection::Right => SnakeDirection::Left,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum SnakeError {
     #[error("Snake hit a wall!")]
@@ -30,7 +42,7 @@ pub trait Snake {
     fn snake_get_body(&self) -> Vec<Coordinates> {
         let snake = self.get_snake();
 
-        if snake.len() == 0 {
+        if snake.is_empty() {
             return vec![];
         }
 
@@ -38,55 +50,41 @@ pub trait Snake {
     }
 
     fn snake_eating_itself(&mut self) -> bool {
-        let snake_head = self.snake_get_head().unwrap().clone();
+        let snake_head = *self.snake_get_head().unwrap();
         let snake_body = self.snake_get_body();
 
         snake_head.intersects_multiple(&snake_body)
     }
 
-    fn get_length(&mut self) -> usize {
-        self.get_snake().len()
-    }
-
     fn snake_get_direction(&self) -> &SnakeDirection;
 
     fn snake_set_direction(&mut self, direction: SnakeDirection);
 
-    fn snake_add_head(&mut self, max_x: usize, max_y: usize) -> Result<(), SnakeError> {
-        let snake_direction = self.snake_get_direction().clone();
-        let (snake_head_x, snake_head_y) =
-            self.snake_get_head().ok_or(SnakeError::NoHead)?.as_tuple();
-        let snake = self.get_snake_mut();
-
-        let new_head_location = match snake_direction {
-            SnakeDirection::Right if snake_head_x < max_x - 1 => {
-                Some(Coordinates::new(snake_head_x + 1, snake_head_y))
-            }
-            SnakeDirection::Left if snake_head_x > 0 => {
-                Some(Coordinates::new(snake_head_x - 1, snake_head_y))
-            }
-            SnakeDirection::Up if snake_head_y > 0 => {
-                Some(Coordinates::new(snake_head_x, snake_head_y - 1))
-            }
-            SnakeDirection::Down if snake_head_y < max_y - 1 => {
-                Some(Coordinates::new(snake_head_x, snake_head_y + 1))
-            }
-            _ => None,
-        };
-
-        if let Some(new_head_location) = new_head_location {
-            snake.push(new_head_location);
-
-            return Ok(());
-        }
+    /// Returns true if the snake should come out of the opposite wall instead of hitting it
+    fn snake_can_wrap(&self) -> bool {
+        false
+    }
 
-        return Err(SnakeError::HitWall);
+    fn snake_add_head(&mut self, max_x: usize, max_y: usize) -> Result<(), SnakeError> {
+        let new_head_location = self
+            .snake_get_head()
+            .ok_or(SnakeError::NoHead)?
+            .step(
+                *self.snake_get_direction(),
+                (max_x, max_y),
+                self.snake_can_wrap(),
+            )
+            .ok_or(SnakeError::HitWall)?;
+
+        self.get_snake_mut().push(new_head_location);
+
+        Ok(())
     }
 
     fn snake_remove_tail(&mut self) {
         let snake = self.get_snake_mut();
 
-        if snake.len() > 0 {
+        if !snake.is_empty() {
             snake.remove(0);
         }
     }
@@ -0,0 +1,435 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum KeymapError {
+    #[error("{0} is bound to both {1} and {2}.")]
+    DuplicateKey(Key, Action, Action),
+    #[error("{0} is bound to {1}, but it's already used for {2} on the {3}.")]
+    ReservedKey(Key, Action, &'static str, &'static str),
+}
+
+/// Something the player can bind keys to
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    TurnUp,
+    TurnDown,
+    TurnLeft,
+    TurnRight,
+    Start,
+    Pause,
+    Restart,
+    Quit,
+    PlayerTwoUp, // Player two's turning keys in a versus game, the arrows if player one has wasd
+    PlayerTwoDown,
+    PlayerTwoLeft,
+    PlayerTwoRight,
+}
+
+/// Written the way the config file names the action
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::TurnUp => "turn_up",
+            Action::TurnDown => "turn_down",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::Start => "start",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::Quit => "quit",
+            Action::PlayerTwoUp => "player_two_up",
+            Action::PlayerTwoDown => "player_two_down",
+            Action::PlayerTwoLeft => "player_two_left",
+            Action::PlayerTwoRight => "player_two_right",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// One thing a key can do on a screen: an action from the keymap, keys the screen always uses, or both when the
+/// screen lets either do the same thing
+struct Meaning {
+    action: Option<Action>,
+    fixed_keys: &'static [KeyCode],
+    used_for: &'static str, // What the fixed keys do, for the error when an action is bound to one
+}
+
+const fn action(action: Action) -> Meaning {
+    Meaning {
+        action: Some(action),
+        fixed_keys: &[],
+        used_for: "",
+    }
+}
+
+const fn fixed(fixed_keys: &'static [KeyCode], used_for: &'static str) -> Meaning {
+    Meaning {
+        action: None,
+        fixed_keys,
+        used_for,
+    }
+}
+
+const fn either(action: Action, fixed_keys: &'static [KeyCode], used_for: &'static str) -> Meaning {
+    Meaning {
+        action: Some(action),
+        fixed_keys,
+        used_for,
+    }
+}
+
+/// Keys listened for at the same time, so no two meanings on a screen can share one
+struct Screen {
+    name: &'static str,
+    meanings: &'static [Meaning],
+}
+
+/// Keys only need to be told apart within a screen, which lets the vim preset turn right with [L] even though [L]
+/// opens the leaderboard on the intro.
+const SCREENS: [Screen; 3] = [
+    Screen {
+        name: "game",
+        meanings: &[
+            action(Action::TurnUp),
+            action(Action::TurnDown),
+            action(Action::TurnLeft),
+            action(Action::TurnRight),
+            action(Action::PlayerTwoUp),
+            action(Action::PlayerTwoDown),
+            action(Action::PlayerTwoLeft),
+            action(Action::PlayerTwoRight),
+            action(Action::Pause),
+        ],
+    },
+    Screen {
+        name: "intro screen",
+        meanings: &[
+            action(Action::Start),
+            action(Action::Quit),
+            fixed(&[KeyCode::Char('v')], "a two player game"),
+            fixed(&[KeyCode::Char('l')], "the leaderboard"),
+            fixed(&[KeyCode::Char('t')], "changing the colours"),
+        ],
+    },
+    Screen {
+        name: "pause menu",
+        meanings: &[
+            either(Action::TurnUp, &[KeyCode::Up], "moving up the menu"),
+            either(Action::TurnDown, &[KeyCode::Down], "moving down the menu"),
+            either(Action::Pause, &[KeyCode::Esc], "resuming"),
+            fixed(&[KeyCode::Enter], "picking a menu item"),
+        ],
+    },
+];
+
+/// A ready made set of keys to start from. Only the turning keys differ between them.
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    #[default]
+    Arrows,
+    Wasd,
+    Vim,
+}
+
+impl FromStr for KeyPreset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "arrows" => Ok(KeyPreset::Arrows),
+            "wasd" => Ok(KeyPreset::Wasd),
+            "vim" => Ok(KeyPreset::Vim),
+            _ => Err(format!("Unknown key preset '{}'.", value)),
+        }
+    }
+}
+
+/// A key as written in the config file, either a single character or the name of a special key
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(try_from = "String")]
+pub struct Key(KeyCode);
+
+impl Key {
+    /// Letters are bound whatever the case, so holding shift or having caps lock on doesn't matter
+    pub fn matches(&self, code: KeyCode) -> bool {
+        match (self.0, code) {
+            (KeyCode::Char(key), KeyCode::Char(code)) => key.eq_ignore_ascii_case(&code),
+            (key, code) => key == code,
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let code = match value.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            key if key.chars().count() == 1 => KeyCode::Char(key.chars().next().unwrap()),
+            _ => {
+                return Err(format!(
+                    "'{}' is not a key, write keys like \"w\", \"up\" or \"space\".",
+                    value
+                ))
+            }
+        };
+
+        Ok(Self(code))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            KeyCode::Up => write!(f, "[UP]"),
+            KeyCode::Down => write!(f, "[DOWN]"),
+            KeyCode::Left => write!(f, "[LEFT]"),
+            KeyCode::Right => write!(f, "[RIGHT]"),
+            KeyCode::Char(' ') => write!(f, "[SPACE]"),
+            KeyCode::Enter => write!(f, "[ENTER]"),
+            KeyCode::Esc => write!(f, "[ESC]"),
+            KeyCode::Tab => write!(f, "[TAB]"),
+            KeyCode::Backspace => write!(f, "[BACKSPACE]"),
+            KeyCode::Char(char) => write!(f, "[{}]", char.to_uppercase()),
+            _ => write!(f, "[?]"),
+        }
+    }
+}
+
+/// Which keys do what. Starts from a preset, and any action listed in the config file gets the keys given for it instead.
+///
+/// ```toml
+/// [keymap]
+/// preset = "wasd"
+/// pause = ["p", "space"]
+/// ```
+#[derive(Deserialize, Clone, PartialEq, Default)]
+pub struct Keymap {
+    #[serde(default)]
+    pub preset: KeyPreset,
+    #[serde(flatten)]
+    pub overrides: HashMap<Action, Vec<Key>>,
+}
+
+impl Keymap {
+    pub fn keys(&self, action: Action) -> Vec<Key> {
+        if let Some(keys) = self.overrides.get(&action) {
+            return keys.clone();
+        }
+
+        let code = match (action, self.preset) {
+            (Action::TurnUp, KeyPreset::Arrows) => KeyCode::Up,
+            (Action::TurnDown, KeyPreset::Arrows) => KeyCode::Down,
+            (Action::TurnLeft, KeyPreset::Arrows) => KeyCode::Left,
+            (Action::TurnRight, KeyPreset::Arrows) => KeyCode::Right,
+            (Action::TurnUp, KeyPreset::Wasd) => KeyCode::Char('w'),
+            (Action::TurnDown, KeyPreset::Wasd) => KeyCode::Char('s'),
+            (Action::TurnLeft, KeyPreset::Wasd) => KeyCode::Char('a'),
+            (Action::TurnRight, KeyPreset::Wasd) => KeyCode::Char('d'),
+            (Action::TurnUp, KeyPreset::Vim) => KeyCode::Char('k'),
+            (Action::TurnDown, KeyPreset::Vim) => KeyCode::Char('j'),
+            (Action::TurnLeft, KeyPreset::Vim) => KeyCode::Char('h'),
+            (Action::TurnRight, KeyPreset::Vim) => KeyCode::Char('l'),
+            (Action::Start, _) => KeyCode::Char(' '),
+            (Action::Pause, _) => KeyCode::Char('p'),
+            (Action::Restart, _) => KeyCode::Char('r'),
+            (Action::Quit, _) => KeyCode::Esc,
+            (Action::PlayerTwoUp, KeyPreset::Wasd) => KeyCode::Up,
+            (Action::PlayerTwoDown, KeyPreset::Wasd) => KeyCode::Down,
+            (Action::PlayerTwoLeft, KeyPreset::Wasd) => KeyCode::Left,
+            (Action::PlayerTwoRight, KeyPreset::Wasd) => KeyCode::Right,
+            (Action::PlayerTwoUp, _) => KeyCode::Char('w'),
+            (Action::PlayerTwoDown, _) => KeyCode::Char('s'),
+            (Action::PlayerTwoLeft, _) => KeyCode::Char('a'),
+            (Action::PlayerTwoRight, _) => KeyCode::Char('d'),
+        };
+
+        vec![Key(code)]
+    }
+
+    /// Returns true if the key is bound to the action
+    pub fn is(&self, action: Action, code: KeyCode) -> bool {
+        self.keys(action).iter().any(|key| key.matches(code))
+    }
+
+    /// Make sure no key means two things at once, which would leave one of them out of reach
+    pub fn validate(&self) -> Result<(), KeymapError> {
+        for screen in &SCREENS {
+            for (index, meaning) in screen.meanings.iter().enumerate() {
+                for other in &screen.meanings[index + 1..] {
+                    self.check_clash(screen, meaning, other)?;
+                    self.check_clash(screen, other, meaning)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Look for a key bound to the first meaning's action that the other meaning uses too
+    fn check_clash(
+        &self,
+        screen: &Screen,
+        meaning: &Meaning,
+        other: &Meaning,
+    ) -> Result<(), KeymapError> {
+        let Some(action) = meaning.action else {
+            return Ok(());
+        };
+
+        for key in self.keys(action) {
+            if let Some(other_action) = other
+                .action
+                .filter(|other_action| self.is(*other_action, key.0))
+            {
+                return Err(KeymapError::DuplicateKey(key, action, other_action));
+            }
+
+            if other.fixed_keys.iter().any(|code| key.matches(*code)) {
+                return Err(KeymapError::ReservedKey(
+                    key,
+                    action,
+                    other.used_for,
+                    screen.name,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The keys bound to the action, written the way the game shows keys, like `[W]` or `[W]/[UP]`
+    pub fn describe(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_listed_in_the_config_replace_the_presets() {
+        let keymap: Keymap = toml::from_str(
+            r#"
+            preset = "vim"
+            pause = ["P", "space"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(keymap.is(Action::TurnRight, KeyCode::Char('l')));
+        assert!(!keymap.is(Action::TurnRight, KeyCode::Right));
+        assert!(keymap.is(Action::Pause, KeyCode::Char('p')));
+        assert!(keymap.is(Action::Pause, KeyCode::Char(' ')));
+        assert_eq!(keymap.describe(Action::Pause), "[P]/[SPACE]");
+        assert!(toml::from_str::<Keymap>(r#"jump = ["j"]"#).is_err());
+        assert!(toml::from_str::<Keymap>(r#"pause = ["f13"]"#).is_err());
+    }
+
+    #[test]
+    fn keys_cant_mean_two_things_on_the_same_screen() {
+        let parse = |config: &str| toml::from_str::<Keymap>(config).unwrap().validate();
+
+        assert!(parse(r#"preset = "vim""#).is_ok());
+        assert!(parse(r#"preset = "wasd""#).is_ok());
+        assert!(parse(r#"pause = ["space"]"#).is_ok());
+        assert!(matches!(
+            parse(r#"pause = ["up"]"#),
+            Err(KeymapError::DuplicateKey(_, Action::TurnUp, Action::Pause))
+        ));
+        assert!(matches!(
+            parse(r#"player_two_left = ["left"]"#),
+            Err(KeymapError::DuplicateKey(
+                _,
+                Action::TurnLeft,
+                Action::PlayerTwoLeft
+            ))
+        ));
+        assert!(matches!(
+            parse(r#"start = ["T"]"#),
+            Err(KeymapError::ReservedKey(
+                _,
+                Action::Start,
+                _,
+                "intro screen"
+            ))
+        ));
+    }
+
+    #[test]
+    fn every_pause_menu_item_stays_in_reach() {
+        let parse = |config: &str| toml::from_str::<Keymap>(config).unwrap().validate();
+
+        assert!(matches!(
+            parse(r#"pause = ["enter"]"#),
+            Err(KeymapError::ReservedKey(
+                _,
+                Action::Pause,
+                "picking a menu item",
+                "pause menu"
+            ))
+        ));
+        assert!(matches!(
+            parse(r#"turn_up = ["esc"]"#),
+            Err(KeymapError::ReservedKey(
+                _,
+                Action::TurnUp,
+                "resuming",
+                "pause menu"
+            ))
+        ));
+        assert!(matches!(
+            parse("turn_up = [\"i\"]\nturn_down = [\"up\"]"),
+            Err(KeymapError::ReservedKey(
+                _,
+                Action::TurnDown,
+                "moving up the menu",
+                "pause menu"
+            ))
+        ));
+
+        // A turning key doing what the arrow key next to it does is fine
+        assert!(parse(r#"turn_up = ["up", "i"]"#).is_ok());
+    }
+
+    #[test]
+    fn player_two_gets_the_arrow_keys_when_player_one_has_wasd() {
+        let wasd = Keymap {
+            preset: KeyPreset::Wasd,
+            ..Keymap::default()
+        };
+
+        assert!(wasd.is(Action::TurnUp, KeyCode::Char('w')));
+        assert!(wasd.is(Action::PlayerTwoUp, KeyCode::Up));
+        assert!(!wasd.is(Action::PlayerTwoUp, KeyCode::Char('w')));
+        assert!(Keymap::default().is(Action::PlayerTwoUp, KeyCode::Char('w')));
+    }
+}
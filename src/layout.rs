@@ -0,0 +1,42 @@
+/// Emoji take up two columns in the terminal, so every cell of the map does too
+pub const CELL_WIDTH: usize = 2;
+
+/// Rows drawn under the map for the score, tick speed, map size and powerup
+pub const HUD_ROWS: usize = 4;
+
+/// The terminal columns and rows needed to draw a map of the given size without it wrapping
+pub fn required_terminal_size(playfield_x: usize, playfield_y: usize) -> (usize, usize) {
+    (playfield_x * CELL_WIDTH, playfield_y + HUD_ROWS)
+}
+
+/// The biggest map that can be drawn in a terminal of the given size
+pub fn fit_playfield(columns: u16, rows: u16) -> (usize, usize) {
+    (
+        columns as usize / CELL_WIDTH,
+        (rows as usize).saturating_sub(HUD_ROWS),
+    )
+}
+
+pub fn fits_terminal(playfield: (usize, usize), columns: u16, rows: u16) -> bool {
+    let (required_columns, required_rows) = required_terminal_size(playfield.0, playfield.1);
+
+    columns as usize >= required_columns && rows as usize >= required_rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_map_leaves_room_for_the_hud_and_two_columns_a_cell() {
+        assert_eq!(required_terminal_size(20, 20), (40, 24));
+        assert_eq!(fit_playfield(80, 24), (40, 20));
+        assert_eq!(fit_playfield(81, 3), (40, 0));
+
+        // Whatever fits a terminal is drawn in it, one column or row less and it doesn't
+        assert!(fits_terminal(fit_playfield(81, 25), 81, 25));
+        assert!(fits_terminal((20, 20), 40, 24));
+        assert!(!fits_terminal((20, 20), 39, 24));
+        assert!(!fits_terminal((20, 20), 40, 23));
+    }
+}
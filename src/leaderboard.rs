@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::game::{Game, GameMode};
+
+/// How many scores the leaderboard keeps
+pub const LEADERBOARD_SIZE: usize = 10;
+
+pub const PLAYER_NAME_MAX_LENGTH: usize = 16;
+
+#[derive(Error, Debug)]
+pub enum LeaderboardError {
+    #[error("Could not access the leaderboard file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not read the leaderboard file: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Could not write the leaderboard file: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("No data directory was found to save the leaderboard in.")]
+    NoDataDir,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u64,
+    pub date: NaiveDate,
+    pub playfield_x: usize,
+    pub playfield_y: usize,
+    pub mode: GameMode,
+    pub duration_secs: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn new(name: String, game: &Game) -> Self {
+        let (playfield_x, playfield_y) = game.get_playfield_size();
+
+        Self {
+            name,
+            score: game.get_score(),
+            date: Local::now().date_naive(),
+            playfield_x,
+            playfield_y,
+            mode: game.get_mode(),
+            duration_secs: game.get_duration().as_secs(),
+        }
+    }
+
+    pub fn get_duration(&self) -> Duration {
+        Duration::from_secs(self.duration_secs)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Leaderboard {
+    entries: Vec<LeaderboardEntry>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    #[serde(skip)]
+    last_error: Option<String>, // Shown on screen, as there is nowhere else to report a failed save mid-game
+    #[serde(skip)]
+    keep_old_file: bool, // The file couldn't be loaded, so move it aside before the first save instead of overwriting it
+}
+
+impl Leaderboard {
+    /// Where the leaderboard lives by default, under the XDG data directory (or the platform equivalent)
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("rust-text-snake").join("leaderboard.toml"))
+    }
+
+    /// Load the leaderboard from the given file, starting an empty one if the file does not exist yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LeaderboardError> {
+        let path = path.as_ref();
+
+        let mut leaderboard: Self = match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(error) => return Err(error.into()),
+        };
+
+        leaderboard.path = Some(path.to_path_buf());
+
+        Ok(leaderboard)
+    }
+
+    /// Start an empty leaderboard in place of a file that couldn't be loaded. The file is moved to the backup path
+    /// before anything is saved, and if it can't be moved nothing is saved, so the old scores are never lost.
+    pub fn replacing<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: Some(path.as_ref().to_path_buf()),
+            keep_old_file: true,
+            ..Self::default()
+        }
+    }
+
+    /// Where an unreadable leaderboard file is moved to
+    pub fn backup_path(path: &Path) -> PathBuf {
+        path.with_extension("toml.bak")
+    }
+
+    pub fn save(&mut self) -> Result<(), LeaderboardError> {
+        let path = self.path.as_ref().ok_or(LeaderboardError::NoDataDir)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if self.keep_old_file {
+            if path.exists() {
+                fs::rename(path, Self::backup_path(path))?;
+            }
+
+            self.keep_old_file = false;
+        }
+
+        fs::write(path, toml::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Insert the entry and save straight away, remembering any error so it can be shown to the player
+    pub fn submit(&mut self, entry: LeaderboardEntry) {
+        self.insert(entry);
+        self.last_error = self.save().err().map(|error| error.to_string());
+    }
+
+    /// Try saving again if the last attempt failed, so a score isn't lost to a problem that has since cleared up
+    pub fn flush(&mut self) -> Result<(), LeaderboardError> {
+        if self.last_error.is_some() {
+            self.save()?;
+            self.last_error = None;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn get_entries(&self) -> &[LeaderboardEntry] {
+        &self.entries
+    }
+
+    /// Returns true if the score would earn a place on the leaderboard
+    pub fn qualifies(&self, score: u64) -> bool {
+        score > 0
+            && (self.entries.len() < LEADERBOARD_SIZE
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    /// Add the entry in score order, dropping whichever entry falls off the bottom
+    pub fn insert(&mut self, entry: LeaderboardEntry) {
+        let position = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(position, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+
+    #[test]
+    fn an_unreadable_leaderboard_is_backed_up_before_saving() {
+        let dir = std::env::temp_dir().join(format!("snake-leaderboard-{}", std::process::id()));
+        let path = dir.join("leaderboard.toml");
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "not a leaderboard").unwrap();
+
+        assert!(Leaderboard::load(&path).is_err());
+
+        let mut leaderboard = Leaderboard::replacing(&path);
+        let game = Game::new(GameConfig::default(), 1);
+
+        leaderboard.submit(LeaderboardEntry::new("Sam".to_string(), &game));
+        leaderboard.submit(LeaderboardEntry::new("Alex".to_string(), &game));
+
+        let backup = fs::read_to_string(Leaderboard::backup_path(&path)).unwrap();
+        let saved = Leaderboard::load(&path).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(backup, "not a leaderboard");
+        assert_eq!(saved.get_entries().len(), 2);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod canvas;
+pub mod cli;
+pub mod clock;
+pub mod color;
+pub mod config;
+pub mod controller;
+pub mod coordinates;
+pub mod frontend;
+pub mod game;
+pub mod keymap;
+pub mod layout;
+pub mod leaderboard;
+pub mod renderer;
+pub mod tasks;
+pub mod terminal;
@@ -1,27 +1,191 @@
-mod canvas;
-mod config;
-mod coordinates;
-mod game;
-mod renderer;
-mod tasks;
+use std::fmt::Display;
 
-use std::sync::{Arc, Mutex};
+use crossterm::terminal;
+use rust_text_snake::{
+    cli::{CliArgs, USAGE},
+    color::ColorMode,
+    config::GameConfig,
+    controller::Controllers,
+    frontend::{ColorTheme, Frontend, ScreenReaderFrontend, TextFrontend, Theme},
+    game::{Game, GlyphTheme, Replay},
+    leaderboard::{Leaderboard, LeaderboardEntry},
+    tasks::{game_loop_task, input_handler_task, render_task},
+    terminal::TerminalGuard,
+};
+use tokio::{
+    sync::{broadcast, mpsc, watch},
+    task::{JoinError, JoinHandle},
+};
 
-use config::PLAYFIELD_SIZE;
-use game::Game;
-use tasks::{game_loop_task, input_handler_task, render_task};
-use tokio::sync::mpsc;
+fn exit_with_error<E: Display>(error: E) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
+
+/// Wait for the task, then tell the others to stop. Whichever task stops first, even if it panicked, takes the
+/// others down with it.
+async fn stop_all_when_done<T>(
+    task: JoinHandle<T>,
+    shutdown_sender: &broadcast::Sender<()>,
+) -> Result<T, JoinError> {
+    let result = task.await;
+    let _ = shutdown_sender.send(());
+
+    result
+}
 
 #[tokio::main]
 async fn main() {
-    let game = Arc::new(Mutex::new(Game::new(PLAYFIELD_SIZE, PLAYFIELD_SIZE)));
+    let cli_args = CliArgs::parse(std::env::args().skip(1)).unwrap_or_else(|e| exit_with_error(e));
+
+    if cli_args.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let mut config = match &cli_args.config {
+        Some(path) => GameConfig::load(path),
+        None => GameConfig::load_default(),
+    }
+    .unwrap_or_else(|e| exit_with_error(e));
+
+    cli_args.apply_to(&mut config);
+
+    let theme = Theme {
+        glyphs: config.glyphs.unwrap_or_else(GlyphTheme::detect),
+        colors: match &config.theme {
+            Some(path) => ColorTheme::load(path).unwrap_or_else(|e| exit_with_error(e)),
+            None => ColorTheme::default(),
+        },
+        palette: config.palette,
+    };
+    let color_mode = config.color.unwrap_or_else(ColorMode::detect);
+    let keymap = config.keymap.clone();
+    let controllers = Controllers::from_config(&config);
+    let frontend: Box<dyn Frontend> = if config.screen_reader {
+        Box::new(ScreenReaderFrontend::new())
+    } else {
+        Box::new(TextFrontend::new(theme, color_mode))
+    };
+
+    let game = match &cli_args.replay {
+        Some(path) => {
+            let mut replay = Replay::load(path).unwrap_or_else(|e| exit_with_error(e));
+
+            // Replays only keep what changes how the game plays, so watch them the way this run asked for
+            replay.config.screen_reader = config.screen_reader;
+            replay.config.keymap = config.keymap.clone();
+            replay
+                .config
+                .validate()
+                .unwrap_or_else(|e| exit_with_error(e));
+
+            let mut game = Game::new_playback(replay);
+
+            game.play();
+            game
+        }
+        None => {
+            if config.fit_terminal {
+                let (columns, rows) = terminal::size().unwrap_or_else(|e| exit_with_error(e));
+
+                config.fit_to_terminal(columns, rows);
+            }
+
+            config.validate().unwrap_or_else(|e| exit_with_error(e));
 
-    let (canvas_sender, canvas_receiver) = mpsc::channel(1);
-    let sender_instance = mpsc::Sender::clone(&canvas_sender);
+            let seed = cli_args.seed.unwrap_or_else(rand::random);
+            let mut game = Game::new(config, seed);
 
-    let _ = tokio::join!(
-        render_task(canvas_receiver),
-        game_loop_task(Arc::clone(&game), sender_instance),
-        input_handler_task(Arc::clone(&game))
+            game.set_keeps_seed(cli_args.seed.is_some());
+            game
+        }
+    };
+
+    let leaderboard = match Leaderboard::default_path() {
+        Some(path) => Leaderboard::load(&path).unwrap_or_else(|error| {
+            eprintln!(
+                "{}\nStarting with an empty leaderboard. The old file will be moved to {} when a score is saved.",
+                error,
+                Leaderboard::backup_path(&path).display()
+            );
+
+            Leaderboard::replacing(&path)
+        }),
+        None => Leaderboard::default(),
+    };
+
+    // A panic in any task would otherwise leave the terminal stuck in raw mode on the alternate screen
+    TerminalGuard::install_panic_hook();
+
+    let terminal_guard = TerminalGuard::new().unwrap_or_else(|e| exit_with_error(e));
+
+    let (frame_sender, frame_receiver) = mpsc::channel(1);
+    let (command_sender, command_receiver) = mpsc::channel(32);
+    let (game_state_sender, game_state_receiver) = watch::channel(game.get_state().clone());
+    let (shutdown_sender, _) = broadcast::channel(1);
+
+    let (render_result, game_loop_result, _) = tokio::join!(
+        stop_all_when_done(
+            render_task(vec![frontend], frame_receiver, shutdown_sender.subscribe()),
+            &shutdown_sender
+        ),
+        stop_all_when_done(
+            game_loop_task(
+                game,
+                leaderboard,
+                controllers,
+                command_receiver,
+                game_state_sender,
+                frame_sender,
+                cli_args.record.clone(),
+                shutdown_sender.subscribe()
+            ),
+            &shutdown_sender
+        ),
+        stop_all_when_done(
+            input_handler_task(
+                command_sender,
+                game_state_receiver,
+                keymap,
+                shutdown_sender.subscribe()
+            ),
+            &shutdown_sender
+        )
     );
+
+    drop(terminal_guard);
+
+    if let Ok(Err(error)) = render_result {
+        eprintln!("Could not draw the game: {}", error);
+    }
+
+    // If the game loop panicked the game went with it, and the panic message has already been printed
+    let Ok((mut game, mut leaderboard)) = game_loop_result else {
+        std::process::exit(1);
+    };
+
+    // Quitting halfway through a game shouldn't lose the replay, and the scores should be on disk before we go
+    // A name that was typed but not submitted yet still counts
+    if let Some(player_name) = game.finish_player_name() {
+        if !player_name.trim().is_empty() {
+            let entry = LeaderboardEntry::new(player_name.trim().to_string(), &game);
+
+            leaderboard.submit(entry);
+        }
+    }
+
+    if let Err(error) = leaderboard.flush() {
+        eprintln!("{}", error);
+    }
+
+    if let Some(path) = &cli_args.record {
+        if !game.is_playback() && game.get_tick() > 0 {
+            if let Err(error) = game.get_replay().save(path) {
+                eprintln!("{}", error);
+            }
+        }
+    }
+
+    println!("Thanks for playing!");
 }
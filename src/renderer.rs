@@ -1,25 +1,204 @@
-use std::process::Command;
+use std::io::{self, Stdout, Write};
 
-use crate::canvas::Canvas;
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
 
-pub struct Renderer {}
+use crate::{
+    canvas::{Canvas, Cell},
+    color::{ColorMode, Style},
+};
+
+/// Draws canvases on the terminal, only writing the cells that changed since the last frame
+pub struct Renderer<W: Write = Stdout> {
+    out: W,
+    previous: Option<Canvas>,
+    color_mode: ColorMode,
+}
 
 impl Renderer {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(color_mode: ColorMode) -> Self {
+        Self {
+            out: io::stdout(),
+            previous: None,
+            color_mode,
+        }
+    }
+}
+
+impl<W: Write> Renderer<W> {
+    /// Forget the last frame, so the next one clears the screen and is drawn in full. A resized terminal can rewrap
+    /// or drop what was on it.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn render(&mut self, canvas: &Canvas) -> io::Result<()> {
+        // What was on the screen before the first frame, or since invalidating, is unknown, so start from a blank one
+        let previous = self.previous.take();
+
+        if previous.is_none() {
+            queue!(self.out, Clear(ClearType::All))?;
+        }
+
+        let previous_rows = previous
+            .as_ref()
+            .map_or(&[][..], |previous| previous.get_rows());
+
+        for (y, row) in canvas.get_rows().iter().enumerate() {
+            match previous_rows.get(y) {
+                Some(previous_row) if previous_row == row => {}
+                Some(previous_row) => self.queue_row_changes(y, previous_row, row)?,
+                None => self.queue_row_changes(y, &[], row)?,
+            }
+        }
+
+        // Blank out anything left over from a taller frame, like the intro screen
+        for y in canvas.get_rows().len()..previous_rows.len() {
+            queue!(self.out, MoveTo(0, y as u16), Clear(ClearType::CurrentLine))?;
+        }
+
+        self.previous = Some(canvas.clone());
+        self.out.flush()
     }
 
-    pub fn render(&self, canvas: &Canvas) {
-        let buf = canvas.to_buffer();
+    /// Write the cells that differ between the two rows. Once a cell changes width every cell after it shifts over,
+    /// so from then on the rest of the row is written out in one go.
+    fn queue_row_changes(
+        &mut self,
+        y: usize,
+        previous_row: &[Cell],
+        row: &[Cell],
+    ) -> io::Result<()> {
+        let mut column = 0;
+
+        for (x, cell) in row.iter().enumerate() {
+            let width = cell.width();
+
+            match previous_row.get(x) {
+                Some(previous_cell) if previous_cell == cell => {}
+                Some(previous_cell) if previous_cell.width() == width => {
+                    queue!(self.out, MoveTo(column as u16, y as u16))?;
+                    self.queue_cells(&row[x..=x])?;
+                }
+                _ => {
+                    queue!(self.out, MoveTo(column as u16, y as u16))?;
+                    self.queue_cells(&row[x..])?;
+
+                    return queue!(self.out, Clear(ClearType::UntilNewLine));
+                }
+            }
+
+            column += width;
+        }
+
+        if previous_row.len() > row.len() {
+            queue!(
+                self.out,
+                MoveTo(column as u16, y as u16),
+                Clear(ClearType::UntilNewLine)
+            )?;
+        }
 
-        println!("{buf}");
+        Ok(())
     }
 
-    pub fn clear(&self) {
-        if cfg!(target_os = "windows") {
-            Command::new("cmd").args(&["/C", "cls"]).status().unwrap();
-        } else {
-            Command::new("clear").status().unwrap();
+    /// Write the cells out in their colours, only changing colour when the style does, and leave the colours reset
+    fn queue_cells(&mut self, cells: &[Cell]) -> io::Result<()> {
+        let mut current = Style::default();
+
+        for cell in cells {
+            if cell.style != current {
+                queue!(self.out, ResetColor)?;
+
+                if let Some(color) = cell
+                    .style
+                    .foreground
+                    .and_then(|rgb| self.color_mode.convert(rgb))
+                {
+                    queue!(self.out, SetForegroundColor(color))?;
+                }
+
+                if let Some(color) = cell
+                    .style
+                    .background
+                    .and_then(|rgb| self.color_mode.convert(rgb))
+                {
+                    queue!(self.out, SetBackgroundColor(color))?;
+                }
+
+                current = cell.style;
+            }
+
+            queue!(self.out, Print(&cell.glyph))?;
         }
+
+        if current != Style::default() {
+            queue!(self.out, ResetColor)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render the rows, returning what was written for them
+    fn render(renderer: &mut Renderer<Vec<u8>>, rows: &[&str]) -> String {
+        let mut canvas = Canvas::new();
+
+        for row in rows {
+            canvas.add_row_string(row);
+        }
+
+        renderer.render(&canvas).unwrap();
+
+        String::from_utf8(std::mem::take(&mut renderer.out)).unwrap()
+    }
+
+    fn renderer() -> Renderer<Vec<u8>> {
+        Renderer {
+            out: Vec::new(),
+            previous: None,
+            color_mode: ColorMode::None,
+        }
+    }
+
+    #[test]
+    fn only_changed_cells_are_written() {
+        let mut renderer = renderer();
+
+        assert_eq!(render(&mut renderer, &["abc"]), "\x1b[2J\x1b[1;1Habc\x1b[K");
+        assert_eq!(render(&mut renderer, &["abc"]), "");
+        assert_eq!(render(&mut renderer, &["axc"]), "\x1b[1;2Hx");
+    }
+
+    #[test]
+    fn a_cell_changing_width_rewrites_the_rest_of_the_row() {
+        let mut renderer = renderer();
+
+        render(&mut renderer, &["abc"]);
+
+        assert_eq!(render(&mut renderer, &["a🍏c"]), "\x1b[1;2H🍏c\x1b[K");
+        assert_eq!(render(&mut renderer, &["a🍏d"]), "\x1b[1;4Hd");
+    }
+
+    #[test]
+    fn shorter_rows_and_frames_are_cleared_after() {
+        let mut renderer = renderer();
+
+        render(&mut renderer, &["abc", "def"]);
+
+        assert_eq!(render(&mut renderer, &["ab", "def"]), "\x1b[1;3H\x1b[K");
+        assert_eq!(render(&mut renderer, &["ab"]), "\x1b[2;1H\x1b[2K");
+
+        renderer.invalidate();
+
+        assert_eq!(render(&mut renderer, &["ab"]), "\x1b[2J\x1b[1;1Hab\x1b[K");
     }
 }
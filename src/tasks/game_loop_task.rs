@@ -1,145 +1,357 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use tokio::{sync::mpsc, task::JoinHandle};
+use crossterm::terminal;
+use tokio::{
+    sync::{broadcast, mpsc, watch},
+    task::JoinHandle,
+};
 
 use crate::{
-    canvas::Canvas,
-    config::PLAYFIELD_SIZE,
-    game::{Characters, Entity, EntityType, Game, GameState, PowerupType, Snake},
+    clock::GameClock,
+    controller::{AutopilotController, Controllers},
+    frontend::Frame,
+    game::{Game, GameCommand, GameMode, GameState, PLAYER_ONE},
+    layout::fits_terminal,
+    leaderboard::{Leaderboard, LeaderboardEntry, PLAYER_NAME_MAX_LENGTH},
 };
 
+/// How long the intro waits without any input before the bot plays a demo game
+const DEMO_DELAY: Duration = Duration::from_secs(10);
+
+/// Owns the game. Ticks it on time, applies commands as they come in, and sends a fresh frame after either.
+/// Before each tick the controllers get to steer their snakes, which is where the keys pressed since the last one go.
+/// Hands the game and leaderboard back when it stops, so anything unsaved can be saved.
+#[allow(clippy::too_many_arguments)]
 pub fn game_loop_task(
-    game: Arc<std::sync::Mutex<Game>>,
-    sender_instance: mpsc::Sender<Canvas>,
-) -> JoinHandle<()> {
+    mut game: Game,
+    mut leaderboard: Leaderboard,
+    mut controllers: Controllers,
+    mut command_receiver: mpsc::Receiver<GameCommand>,
+    game_state: watch::Sender<GameState>,
+    sender_instance: mpsc::Sender<Frame>,
+    record_path: Option<PathBuf>,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<(Game, Leaderboard)> {
     tokio::spawn(async move {
+        let mut replay_status: Option<String> = None;
+        let mut leaderboard_checked = false;
+        let mut clock = GameClock::new(Duration::from_millis(game.get_tick_speed()));
+        let mut debug_hud = false;
+        let mut demo: Option<Game> = None; // Shown instead of the intro, and thrown away as soon as there's input
+        let mut idle_since = Instant::now();
+        let mut demo_controllers = Controllers::default();
+        let mut terminal_size = terminal::size().ok(); // The last size heard of, to check the map against as it grows
+        let mut redraw = false;
+        let mut next_palette = false;
+
+        demo_controllers.assign(PLAYER_ONE, Box::new(AutopilotController));
+        suspend_unless_fits(&mut game, terminal_size);
+
         loop {
-            let (canvas, tick_speed) = {
-                let mut game = game.lock().unwrap();
-                let game_state = game.next();
-
-                let final_canvas = match game_state {
-                    GameState::Playing => {
-                        let mut canvas = Canvas::new();
-
-                        let score = game.get_score();
-                        let snake_body = game.snake_get_body();
-                        let snake_head = game.snake_get_head().unwrap();
-
-                        let score_render = format!("Score: {}", score);
-                        let speed_display = format!("Tick speed (ms): {}", game.get_tick_speed());
-
-                        let powerup_display = format!(
-                            "Powerup ticks: {}",
-                            match game.get_current_powerup() {
-                                PowerupType::Supersnake { tick_duration } =>
-                                    tick_duration.to_string(),
-                                PowerupType::Slowdown { tick_duration } =>
-                                    tick_duration.to_string(),
-                                PowerupType::None => "No powerup active".to_string(),
-                            }
-                        );
-
-                        canvas.fill(Characters::Grass.value(), PLAYFIELD_SIZE, PLAYFIELD_SIZE);
-                        canvas.add_row_string(score_render);
-                        canvas.add_row_string(speed_display);
-                        canvas.add_row_string(powerup_display);
-
-                        for entity in game.get_all_entities() {
-                            match entity {
-                                EntityType::SupersnakePwrup { .. } => {
-                                    canvas.set_coord(
-                                        entity.get_coordinates().unwrap(),
-                                        Characters::SupersnakePwrup.value(),
-                                    );
-                                }
-                                EntityType::SlowdownPwrup { .. } => {
-                                    canvas.set_coord(
-                                        entity.get_coordinates().unwrap(),
-                                        Characters::SlowdownPwrup.value(),
-                                    );
-                                }
-                                EntityType::Apple { .. } => {
-                                    canvas.set_coord(
-                                        entity.get_coordinates().unwrap(),
-                                        Characters::Apple.value(),
-                                    );
-                                }
-                                EntityType::Obstacle { .. } => {
-                                    canvas.set_coord(
-                                        entity.get_coordinates().unwrap(),
-                                        Characters::Obstacle.value(),
-                                    );
-                                }
-                            };
+            tokio::select! {
+                _ = shutdown.recv() => break,
+                command = command_receiver.recv() => {
+                    demo = None;
+                    idle_since = Instant::now();
+
+                    match command {
+                        Some(GameCommand::Quit) | None => break,
+                        Some(GameCommand::ToggleDebugHud) => debug_hud = !debug_hud,
+                        Some(GameCommand::CyclePalette) => next_palette = true,
+                        Some(GameCommand::Resize(columns, rows)) => {
+                            terminal_size = Some((columns, rows));
+                            redraw = true;
+                            suspend_unless_fits(&mut game, terminal_size);
                         }
+                        Some(command) => apply_command(&mut game, &mut leaderboard, &mut controllers, command),
+                    }
+                },
+                _ = clock.tick() => {
+                    match &mut demo {
+                        Some(demo_game) => {
+                            demo_controllers.steer(demo_game);
+                            demo_game.next();
 
-                        for coordinate in snake_body {
-                            match game.get_current_powerup() {
-                                PowerupType::Supersnake { .. } => canvas
-                                    .set_coord(&coordinate, Characters::SnakeBodySuper.value()),
-                                PowerupType::Slowdown { .. } => {
-                                    canvas.set_coord(&coordinate, Characters::SnakeBodySlow.value())
-                                }
-                                PowerupType::None => {
-                                    canvas.set_coord(&coordinate, Characters::SnakeBody.value())
-                                }
-                            };
+                            let outgrown = terminal_size.is_some_and(|(columns, rows)| {
+                                !fits_terminal(demo_game.get_playfield_size(), columns, rows)
+                            });
+
+                            if outgrown || matches!(demo_game.get_state(), GameState::GameOver { .. }) {
+                                demo = None;
+                                idle_since = Instant::now();
+                            }
                         }
+                        None => {
+                            let playfield_size = game.get_playfield_size();
+
+                            controllers.steer(&mut game);
+                            game.next();
+
+                            // The map grow powerup can take the map past the edge of the terminal
+                            if game.get_playfield_size() != playfield_size {
+                                suspend_unless_fits(&mut game, terminal_size);
+                            }
 
-                        canvas.set_coord(&snake_head, Characters::SnakeHead.value());
+                            if wants_demo(&game) && idle_since.elapsed() >= DEMO_DELAY {
+                                let mut demo_game = Game::new(game.get_config().clone(), rand::random());
 
-                        canvas
+                                demo_game.play();
+                                demo = Some(demo_game);
+                            }
+                        }
                     }
-                    GameState::Intro => {
-                        let mut canvas = Canvas::new();
-
-                        canvas.add_row_string("Welcome to Snake!");
-                        canvas.add_row_string(format!(
-                            "{}{}{}",
-                            Characters::SnakeBody.value(),
-                            Characters::SnakeBody.value(),
-                            Characters::SnakeHead.value()
-                        ));
-                        canvas.add_row_string("Use the arrow keys to move.");
-                        canvas.add_row_string("");
-                        canvas.add_row_string(format!(
-                            "{} - Eat to grow.",
-                            Characters::Apple.value()
-                        ));
-                        canvas.add_row_string(format!(
-                            "{} - Eat to become invincible to obstacles.",
-                            Characters::SupersnakePwrup.value()
-                        ));
-                        canvas.add_row_string(format!("{} - Eat to slow down time, but if you spam-press the arrow key they corresponds to your current direction time speeds up!", Characters::SlowdownPwrup.value()));
-                        canvas.add_row_string(format!(
-                            "{} - Avoid or game over!",
-                            Characters::Obstacle.value()
-                        ));
-                        canvas.add_row_string("");
-                        canvas.add_row_string("Press [SPACE] to start.");
-                        canvas.add_row_string(
-                            "You can quit at any time by pressing [ESC] in this screen.",
-                        );
-
-                        canvas
+
+                    // Powerups and points change the speed, so the clock picks up the new period straight away
+                    let ticking = demo.as_ref().unwrap_or(&game);
+
+                    clock.set_period(Duration::from_millis(ticking.get_tick_speed()));
+                }
+            }
+
+            // Only ask for a name once per game, otherwise skipping the prompt would bring it straight back.
+            // Versus scores are shared between two players and a bot's scores aren't anyone's, so neither go on it.
+            match game.get_state() {
+                GameState::Playing => leaderboard_checked = false,
+                GameState::GameOver { score, .. } if !leaderboard_checked => {
+                    leaderboard_checked = true;
+
+                    if !game.is_playback()
+                        && game.get_mode() == GameMode::Classic
+                        && controllers.takes_keys(PLAYER_ONE)
+                        && leaderboard.qualifies(*score)
+                    {
+                        game.prompt_player_name();
                     }
-                    GameState::GameOver { score, message } => {
-                        let mut canvas = Canvas::new();
+                }
+                _ => (),
+            }
 
-                        canvas.add_row_string(message);
-                        canvas.add_row_string(format!("Final score: {}", score));
+            game_state.send_replace(game.get_state().clone());
 
-                        canvas
+            // Save the replay once, as soon as the game ends
+            match game.get_state() {
+                GameState::Playing => replay_status = None,
+                GameState::GameOver { .. } if replay_status.is_none() && !game.is_playback() => {
+                    if let Some(path) = &record_path {
+                        replay_status = Some(match game.get_replay().save(path) {
+                            Ok(_) => format!("Replay saved to {}.", path.display()),
+                            Err(error) => error.to_string(),
+                        });
                     }
-                };
+                }
+                _ => (),
+            }
 
-                (final_canvas, game.get_tick_speed())
+            // The input handler keeps being told the real game's state, so keys still work the intro while the demo plays
+            let shown = demo.as_mut().unwrap_or(&mut game);
+            let frame = Frame {
+                game: shown.snapshot(),
+                leaderboard: leaderboard.get_entries().to_vec(),
+                leaderboard_error: leaderboard.get_last_error().map(String::from),
+                replay_status: replay_status.clone(),
+                tick_timing: debug_hud.then(|| clock.get_timing()),
+                events: shown.take_events(),
+                demo: demo.is_some(),
+                redraw: std::mem::take(&mut redraw),
+                next_palette: std::mem::take(&mut next_palette),
             };
 
-            sender_instance.send(canvas).await.unwrap();
-
-            tokio::time::sleep(Duration::from_millis(tick_speed)).await;
+            // The render task only goes away when the game is shutting down
+            if sender_instance.send(frame).await.is_err() {
+                break;
+            }
         }
+
+        (game, leaderboard)
     })
 }
+
+/// The demo only takes over an intro nobody is using. Screen reader users get nothing from watching it, and a replay
+/// waiting to be started shouldn't be mixed up with one.
+fn wants_demo(game: &Game) -> bool {
+    matches!(game.get_state(), GameState::Intro)
+        && !game.is_playback()
+        && !game.is_suspended()
+        && !game.get_config().screen_reader
+}
+
+/// Drawing a map bigger than the terminal wraps every row into a mess, so hold the game until it fits again.
+/// The screen reader mode never draws the map, so any size will do.
+fn suspend_unless_fits(game: &mut Game, terminal_size: Option<(u16, u16)>) {
+    if let Some((columns, rows)) = terminal_size {
+        let fits = fits_terminal(game.get_playfield_size(), columns, rows);
+
+        game.set_suspended(!fits && !game.get_config().screen_reader);
+    }
+}
+
+fn apply_command(
+    game: &mut Game,
+    leaderboard: &mut Leaderboard,
+    controllers: &mut Controllers,
+    command: GameCommand,
+) {
+    // Nothing can be seen while the game is held for a small terminal, so only a resize that could let it carry on gets through
+    if game.is_suspended() && !matches!(command, GameCommand::Resize(..)) {
+        return;
+    }
+
+    match command {
+        // Keys wait for the next tick in the snake's controller
+        GameCommand::Turn(id, direction) => controllers.press(game, id, direction),
+        // Whatever is still waiting belongs to the game before, so the controllers start afresh with a new one
+        GameCommand::SetMode(mode) => {
+            game.set_mode(mode);
+            controllers.reset();
+        }
+        GameCommand::Start => {
+            game.play();
+            controllers.reset();
+        }
+        GameCommand::Pause => game.pause(),
+        GameCommand::Resume => game.resume(),
+        GameCommand::Restart => {
+            game.start_over();
+            controllers.reset();
+        }
+        GameCommand::Quit
+        | GameCommand::ToggleDebugHud
+        | GameCommand::Resize(..)
+        | GameCommand::CyclePalette => (),
+        GameCommand::ShowLeaderboard => game.show_leaderboard(),
+        GameCommand::ShowIntro => game.show_intro(),
+        GameCommand::SelectPauseMenuItem(item) => game.select_pause_menu_item(item),
+        GameCommand::ToggleSettings => game.toggle_settings(),
+        GameCommand::TypeName(char) => {
+            if let Some(player_name) = game.get_player_name_mut() {
+                if player_name.chars().count() < PLAYER_NAME_MAX_LENGTH {
+                    player_name.push(char);
+                }
+            }
+        }
+        GameCommand::EraseName => {
+            if let Some(player_name) = game.get_player_name_mut() {
+                player_name.pop();
+            }
+        }
+        GameCommand::SubmitName => {
+            let has_name = game
+                .get_player_name_mut()
+                .is_some_and(|player_name| !player_name.trim().is_empty());
+
+            if has_name {
+                let player_name = game.finish_player_name().unwrap();
+                let entry = LeaderboardEntry::new(player_name.trim().to_string(), game);
+
+                leaderboard.submit(entry);
+            }
+        }
+        GameCommand::SkipName => {
+            game.finish_player_name();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::GameConfig,
+        game::{Snake, SnakeDirection, PLAYER_TWO},
+    };
+
+    #[test]
+    fn commands_move_the_game_between_its_screens() {
+        let mut game = Game::new(GameConfig::default(), 1);
+        let mut leaderboard = Leaderboard::default();
+        let mut controllers = Controllers::from_config(game.get_config());
+        let mut apply = |game: &mut Game, command| {
+            apply_command(game, &mut leaderboard, &mut controllers, command)
+        };
+
+        apply(&mut game, GameCommand::ShowLeaderboard);
+        assert!(matches!(game.get_state(), GameState::Leaderboard));
+
+        apply(&mut game, GameCommand::ShowIntro);
+        apply(&mut game, GameCommand::SetMode(GameMode::Versus));
+        apply(&mut game, GameCommand::Start);
+        assert!(matches!(game.get_state(), GameState::Playing));
+
+        // The mode is only picked on the intro
+        apply(&mut game, GameCommand::SetMode(GameMode::Classic));
+        assert!(game.get_mode() == GameMode::Versus);
+
+        apply(&mut game, GameCommand::Pause);
+        assert!(matches!(game.get_state(), GameState::Paused { .. }));
+
+        apply(&mut game, GameCommand::Resume);
+        assert!(matches!(game.get_state(), GameState::Playing));
+
+        apply(&mut game, GameCommand::Restart);
+        assert!(matches!(game.get_state(), GameState::Intro));
+        assert!(game.get_mode() == GameMode::Versus);
+
+        apply(&mut game, GameCommand::SetMode(GameMode::Classic));
+        assert!(game.get_mode() == GameMode::Classic);
+
+        // Held for a small terminal, the game waits for a resize and ignores the rest
+        game.set_suspended(true);
+        apply(&mut game, GameCommand::Start);
+        assert!(matches!(game.get_state(), GameState::Intro));
+
+        game.set_suspended(false);
+        apply(&mut game, GameCommand::Start);
+
+        while !matches!(game.get_state(), GameState::GameOver { .. }) {
+            game.next();
+        }
+
+        // Names are only typed once the game over screen asks for one
+        apply(&mut game, GameCommand::TypeName('x'));
+        game.prompt_player_name();
+
+        for command in [
+            GameCommand::TypeName('a'),
+            GameCommand::TypeName('b'),
+            GameCommand::EraseName,
+            GameCommand::SubmitName,
+        ] {
+            apply(&mut game, command);
+        }
+
+        assert!(game.get_player_name_mut().is_none());
+        assert_eq!(leaderboard.get_entries().len(), 1);
+        assert_eq!(leaderboard.get_entries()[0].name, "a");
+    }
+
+    #[test]
+    fn keys_pressed_for_one_game_dont_turn_up_in_the_next() {
+        let mut game = Game::new(GameConfig::default(), 1);
+        let mut leaderboard = Leaderboard::default();
+        let mut controllers = Controllers::from_config(game.get_config());
+
+        // Player two has no snake in a classic game, and player one's key is still waiting when it's restarted
+        for command in [
+            GameCommand::Start,
+            GameCommand::Turn(PLAYER_TWO, SnakeDirection::Up),
+            GameCommand::Turn(PLAYER_ONE, SnakeDirection::Down),
+            GameCommand::Pause,
+            GameCommand::Restart,
+            GameCommand::SetMode(GameMode::Versus),
+            GameCommand::Start,
+        ] {
+            apply_command(&mut game, &mut leaderboard, &mut controllers, command);
+        }
+
+        controllers.steer(&mut game);
+        game.next();
+
+        let direction = |id| game.get_snake(id).map(|snake| *snake.snake_get_direction());
+
+        assert_eq!(direction(PLAYER_ONE), Some(SnakeDirection::Right));
+        assert_eq!(direction(PLAYER_TWO), Some(SnakeDirection::Left));
+    }
+}
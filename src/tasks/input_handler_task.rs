@@ -1,59 +1,156 @@
-use std::sync::Arc;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use tokio::{
+    sync::{broadcast, mpsc, watch},
+    task::JoinHandle,
+};
 
-use crossterm::event::{read, Event, KeyCode, KeyEventKind};
-use tokio::task::JoinHandle;
+use crate::{
+    game::{
+        GameCommand, GameMode, GameState, PauseMenuItem, SnakeDirection, PLAYER_ONE, PLAYER_TWO,
+    },
+    keymap::{Action, Keymap},
+};
 
-use crate::game::{Game, GameState, Snake, SnakeDirection};
-
-pub fn input_handler_task(game: Arc<std::sync::Mutex<Game>>) -> JoinHandle<()> {
+/// Turns terminal events into commands for the game loop. The current game state decides what each key means,
+/// so the same key can steer the snake in one screen and move through a menu in another.
+pub fn input_handler_task(
+    command_sender: mpsc::Sender<GameCommand>,
+    game_state: watch::Receiver<GameState>,
+    keymap: Keymap,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let mut last_key = KeyCode::Null;
+        let mut events = EventStream::new();
 
         loop {
-            if let Ok(Event::Key(key_event)) = read() {
-                let mut game = game.lock().unwrap();
-
-                // This thread polls very quickly, so we need to make sure we don't poll the same key twice
-                match key_event.kind {
-                    KeyEventKind::Press if (key_event.code == last_key) => continue,
-                    KeyEventKind::Press => last_key = key_event.code,
-                    KeyEventKind::Repeat => continue,
-                    KeyEventKind::Release => {
-                        last_key = KeyCode::Null;
-
-                        continue;
-                    }
+            let event = tokio::select! {
+                _ = shutdown.recv() => break,
+                event = events.next() => match event {
+                    Some(Ok(event)) => event,
+                    _ => break,
+                },
+            };
+
+            let commands = match event {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    commands_for_key(&game_state.borrow(), &keymap, key_event)
                 }
+                Event::FocusLost => vec![GameCommand::Pause],
+                Event::Resize(columns, rows) => vec![GameCommand::Resize(columns, rows)],
+                _ => continue,
+            };
 
-                match game.get_state() {
-                    GameState::Playing => {
-                        // The matches! statements are used to stop people from accidentally eating the snake
-                        match key_event.code {
-                            KeyCode::Up => game.snake_set_direction(SnakeDirection::Up),
-                            KeyCode::Left => game.snake_set_direction(SnakeDirection::Left),
-                            KeyCode::Down => game.snake_set_direction(SnakeDirection::Down),
-                            KeyCode::Right => game.snake_set_direction(SnakeDirection::Right),
-                            _ => (),
-                        }
-                    }
-                    GameState::Intro => match key_event.code {
-                        KeyCode::Char(' ') => {
-                            game.play();
-                        }
-                        KeyCode::Esc => {
-                            println!("Thanks for playing!");
-                            std::process::exit(0);
-                        }
-                        _ => (),
-                    },
-                    GameState::GameOver { .. } => match key_event.code {
-                        KeyCode::Char('r') => {
-                            game.start_over();
-                        }
-                        _ => (),
-                    },
+            for command in commands {
+                if command_sender.send(command).await.is_err() {
+                    return;
                 }
             }
         }
     })
 }
+
+fn commands_for_key(
+    game_state: &GameState,
+    keymap: &Keymap,
+    key_event: KeyEvent,
+) -> Vec<GameCommand> {
+    // Raw mode swallows the interrupt signal, so Ctrl+C has to be handled like any other key
+    if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+        return vec![GameCommand::Quit];
+    }
+
+    if key_event.code == KeyCode::F(3) {
+        return vec![GameCommand::ToggleDebugHud];
+    }
+
+    let command = match game_state {
+        GameState::GameOver {
+            player_name: Some(_),
+            ..
+        } => match key_event.code {
+            KeyCode::Char(char) if char.is_alphanumeric() || char == ' ' => {
+                GameCommand::TypeName(char)
+            }
+            KeyCode::Backspace => GameCommand::EraseName,
+            KeyCode::Enter => GameCommand::SubmitName,
+            KeyCode::Esc => GameCommand::SkipName,
+            _ => return vec![],
+        },
+        GameState::Paused {
+            showing_settings: true,
+            ..
+        } => match key_event.code {
+            KeyCode::Esc => GameCommand::ToggleSettings,
+            _ => return vec![],
+        },
+        // The turning keys move through the menu too, so the arrow keys aren't needed with another preset
+        GameState::Paused { selected, .. } => match key_event.code {
+            code if code == KeyCode::Up || keymap.is(Action::TurnUp, code) => {
+                GameCommand::SelectPauseMenuItem(selected.previous())
+            }
+            code if code == KeyCode::Down || keymap.is(Action::TurnDown, code) => {
+                GameCommand::SelectPauseMenuItem(selected.next())
+            }
+            code if code == KeyCode::Esc || keymap.is(Action::Pause, code) => GameCommand::Resume,
+            KeyCode::Enter => match selected {
+                PauseMenuItem::Resume => GameCommand::Resume,
+                PauseMenuItem::Restart => return vec![GameCommand::Restart, GameCommand::Start],
+                PauseMenuItem::Settings => GameCommand::ToggleSettings,
+                PauseMenuItem::Quit => GameCommand::Quit,
+            },
+            _ => return vec![],
+        },
+        // Player two's keys are sent in every mode, a game without a second snake ignores them
+        GameState::Playing => match key_event.code {
+            code if keymap.is(Action::TurnUp, code) => {
+                GameCommand::Turn(PLAYER_ONE, SnakeDirection::Up)
+            }
+            code if keymap.is(Action::TurnLeft, code) => {
+                GameCommand::Turn(PLAYER_ONE, SnakeDirection::Left)
+            }
+            code if keymap.is(Action::TurnDown, code) => {
+                GameCommand::Turn(PLAYER_ONE, SnakeDirection::Down)
+            }
+            code if keymap.is(Action::TurnRight, code) => {
+                GameCommand::Turn(PLAYER_ONE, SnakeDirection::Right)
+            }
+            code if keymap.is(Action::PlayerTwoUp, code) => {
+                GameCommand::Turn(PLAYER_TWO, SnakeDirection::Up)
+            }
+            code if keymap.is(Action::PlayerTwoLeft, code) => {
+                GameCommand::Turn(PLAYER_TWO, SnakeDirection::Left)
+            }
+            code if keymap.is(Action::PlayerTwoDown, code) => {
+                GameCommand::Turn(PLAYER_TWO, SnakeDirection::Down)
+            }
+            code if keymap.is(Action::PlayerTwoRight, code) => {
+                GameCommand::Turn(PLAYER_TWO, SnakeDirection::Right)
+            }
+            code if keymap.is(Action::Pause, code) => GameCommand::Pause,
+            _ => return vec![],
+        },
+        GameState::Intro => match key_event.code {
+            code if keymap.is(Action::Start, code) => {
+                return vec![GameCommand::SetMode(GameMode::Classic), GameCommand::Start]
+            }
+            KeyCode::Char('v' | 'V') => {
+                return vec![GameCommand::SetMode(GameMode::Versus), GameCommand::Start]
+            }
+            code if keymap.is(Action::Quit, code) => GameCommand::Quit,
+            KeyCode::Char('l' | 'L') => GameCommand::ShowLeaderboard,
+            KeyCode::Char('t' | 'T') => GameCommand::CyclePalette,
+            _ => return vec![],
+        },
+        GameState::Leaderboard => match key_event.code {
+            KeyCode::Char('l' | 'L') | KeyCode::Esc => GameCommand::ShowIntro,
+            _ => return vec![],
+        },
+        GameState::GameOver { .. } => match key_event.code {
+            code if keymap.is(Action::Restart, code) => GameCommand::Restart,
+            _ => return vec![],
+        },
+    };
+
+    vec![command]
+}
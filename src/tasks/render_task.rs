@@ -1,16 +1,34 @@
-use tokio::{sync::mpsc::Receiver, task::JoinHandle};
+use std::io;
 
-use crate::{canvas::Canvas, renderer::Renderer};
+use tokio::{
+    sync::{broadcast, mpsc::Receiver},
+    task::JoinHandle,
+};
 
-pub fn render_task(mut canvas_receiver: Receiver<Canvas>) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        let renderer = Renderer::new();
+use crate::frontend::{Frame, Frontend};
 
+/// Hands every frame to each of the frontends in turn. Stops at the first frame that can't be drawn, which takes the
+/// other tasks down with it instead of carrying on with nothing on the screen.
+pub fn render_task(
+    mut frontends: Vec<Box<dyn Frontend>>,
+    mut frame_receiver: Receiver<Frame>,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<io::Result<()>> {
+    tokio::spawn(async move {
         loop {
-            let canvas: Canvas = canvas_receiver.recv().await.unwrap();
+            let frame = tokio::select! {
+                _ = shutdown.recv() => break,
+                frame = frame_receiver.recv() => match frame {
+                    Some(frame) => frame,
+                    None => break,
+                },
+            };
 
-            renderer.clear();
-            renderer.render(&canvas);
+            for frontend in &mut frontends {
+                frontend.draw(&frame)?;
+            }
         }
+
+        Ok(())
     })
 }
@@ -0,0 +1,48 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{DisableFocusChange, EnableFocusChange},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Puts the terminal into the state the game needs for as long as it lives, and puts it back when dropped.
+///
+/// Raw mode lets keys through as soon as they are pressed, the alternate screen keeps the game from scribbling over
+/// the shell, and focus changes are reported so the game can pause when the terminal loses focus.
+pub struct TerminalGuard {}
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableFocusChange, Hide)?;
+
+        Ok(Self {})
+    }
+
+    /// Put the terminal back the way it was. Safe to call more than once, and from a panic hook.
+    pub fn restore() {
+        let mut stdout = io::stdout();
+
+        let _ = execute!(stdout, Show, DisableFocusChange, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+        let _ = stdout.flush();
+    }
+
+    /// Restore the terminal before the default panic message is printed, so it isn't lost on the alternate screen
+    pub fn install_panic_hook() {
+        let default_panic_hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            Self::restore();
+            default_panic_hook(info);
+        }));
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}